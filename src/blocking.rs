@@ -0,0 +1,477 @@
+//! A blocking API for applications that don't run an async runtime. It
+//! provides the same operations as the rest of the crate, but as plain
+//! functions that return once the response is received. Paginated resources
+//! are provided as [`Iter`]ators instead of streams.
+//!
+//! The functions must not be called from within an async runtime.
+//!
+//! The requests are sent on the calling thread, so a client can be used from
+//! multiple threads at once without the requests waiting for each other.
+
+use crate::{
+    cache::Cache,
+    rate_limit::{Budget, RateLimit},
+    retry::RetryPolicy,
+    Error,
+};
+use futures_util::stream::{Stream, StreamExt};
+use http::header::InvalidHeaderValue;
+use std::{convert::TryFrom, future::Future, io, pin::Pin, sync::Arc, thread};
+use tokio::{
+    runtime::{Builder, Handle},
+    sync::oneshot,
+};
+
+/// The blocking client that is used to send requests to speedrun.com. It
+/// drives an async [`Client`](crate::Client) with a runtime that runs on a
+/// background thread. Cloning it is cheap, as the runtime is shared between
+/// the clones.
+#[derive(Clone)]
+pub struct Client {
+    client: crate::Client,
+    runtime: Arc<Runtime>,
+}
+
+/// The runtime that drives the I/O and timers of the requests, as well as the
+/// connections of the transport. It shuts down once all the clients using it
+/// are dropped.
+struct Runtime {
+    handle: Handle,
+    _shutdown: oneshot::Sender<()>,
+}
+
+impl Runtime {
+    fn spawn() -> io::Result<Self> {
+        let mut runtime = Builder::new().basic_scheduler().enable_all().build()?;
+        let handle = runtime.handle().clone();
+        let (shutdown, stopped) = oneshot::channel();
+
+        thread::Builder::new()
+            .name("speedrun-com-api".into())
+            .spawn(move || {
+                // The sender is never used, so this only completes once it is
+                // dropped.
+                let _ = runtime.block_on(stopped);
+            })?;
+
+        Ok(Self {
+            handle,
+            _shutdown: shutdown,
+        })
+    }
+}
+
+impl TryFrom<crate::Client> for Client {
+    type Error = io::Error;
+
+    /// Drives the async client on a new runtime. This fails if the runtime
+    /// can't be started.
+    fn try_from(client: crate::Client) -> io::Result<Self> {
+        Ok(Self {
+            client,
+            runtime: Arc::new(Runtime::spawn()?),
+        })
+    }
+}
+
+impl Client {
+    /// Creates a new client using the default transport. This fails if the
+    /// runtime can't be started.
+    #[cfg(feature = "hyper-transport")]
+    pub fn new() -> io::Result<Self> {
+        Self::try_from(crate::Client::new())
+    }
+
+    /// Authenticates all the requests with the given API key. The API key can
+    /// be found in the user's speedrun.com settings.
+    pub fn with_api_key(mut self, api_key: &str) -> Result<Self, InvalidHeaderValue> {
+        self.client = self.client.with_api_key(api_key)?;
+        Ok(self)
+    }
+
+    /// Limits the rate at which requests are sent. See
+    /// [`Client::with_rate_limit`](crate::Client::with_rate_limit).
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.client = self.client.with_rate_limit(rate_limit);
+        self
+    }
+
+    /// Returns the current budget of the rate limiter, if there is one.
+    pub fn rate_limit_budget(&self) -> Option<Budget> {
+        self.client.rate_limit_budget()
+    }
+
+    /// Retries `GET` requests that failed for transient reasons. See
+    /// [`Client::with_retry`](crate::Client::with_retry).
+    pub fn with_retry(mut self, retry_policy: RetryPolicy) -> Self {
+        self.client = self.client.with_retry(retry_policy);
+        self
+    }
+
+    /// Answers `GET` requests from the cache while the cached responses are
+    /// fresh. See [`Client::with_cache`](crate::Client::with_cache).
+    pub fn with_cache(mut self, cache: Cache) -> Self {
+        self.client = self.client.with_cache(cache);
+        self
+    }
+
+    /// Accesses the async client that is driven by this client.
+    pub fn as_async(&self) -> &crate::Client {
+        &self.client
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.handle.block_on(future)
+    }
+
+    fn iter<'a, T>(&'a self, stream: impl Stream<Item = Result<T, Error>> + 'a) -> Iter<'a, T> {
+        Iter {
+            client: self,
+            stream: Box::pin(stream),
+        }
+    }
+}
+
+/// An iterator over a paginated resource. The next page is requested once all
+/// the elements of the current page are consumed.
+pub struct Iter<'a, T> {
+    client: &'a Client,
+    stream: Pin<Box<dyn Stream<Item = Result<T, Error>> + 'a>>,
+}
+
+impl<T> Iterator for Iter<'_, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stream = &mut self.stream;
+        self.client.block_on(stream.next())
+    }
+}
+
+macro_rules! all_and_by_id {
+    ($module:ident, $resource:ident, $id:ident) => {
+        pub mod $module {
+            use super::{Client, Iter};
+            use crate::{common::$id, $module::$resource, Error};
+
+            pub fn all(client: &Client, elements_per_page: Option<u16>) -> Iter<'_, $resource> {
+                client.iter(crate::$module::all(&client.client, elements_per_page))
+            }
+
+            pub fn by_id(client: &Client, id: $id) -> Result<$resource, Error> {
+                client.block_on(crate::$module::by_id(&client.client, id))
+            }
+        }
+    };
+}
+
+all_and_by_id!(developers, Developer, DeveloperId);
+all_and_by_id!(engines, Engine, EngineId);
+all_and_by_id!(gametypes, GameType, GameTypeId);
+all_and_by_id!(genres, Genre, GenreId);
+all_and_by_id!(platforms, Platform, PlatformId);
+all_and_by_id!(publishers, Publisher, PublisherId);
+all_and_by_id!(regions, Region, RegionId);
+
+pub mod categories {
+    use super::{Client, Iter};
+    use crate::{
+        categories::{Category, Embeds},
+        common::{CategoryId, GameId},
+        leaderboards::RecordsQuery,
+        Error, Leaderboard,
+    };
+
+    pub fn for_game(
+        client: &Client,
+        game_id: GameId,
+        embeds: Embeds,
+    ) -> Result<Vec<Category>, Error> {
+        client.block_on(crate::categories::for_game(&client.client, game_id, embeds))
+    }
+
+    pub fn by_id(
+        client: &Client,
+        category_id: CategoryId,
+        embeds: Embeds,
+    ) -> Result<Category, Error> {
+        client.block_on(crate::categories::by_id(
+            &client.client,
+            category_id,
+            embeds,
+        ))
+    }
+
+    pub fn records<'client>(
+        client: &'client Client,
+        category_id: CategoryId,
+        query: &RecordsQuery,
+    ) -> Iter<'client, Leaderboard> {
+        client.iter(crate::categories::records(
+            &client.client,
+            category_id,
+            query,
+        ))
+    }
+}
+
+pub mod games {
+    use super::{Client, Iter};
+    use crate::{
+        common::GameId,
+        games::{Embeds, Game, GameHeader, GameQuery},
+        leaderboards::RecordsQuery,
+        Error, Leaderboard,
+    };
+
+    pub fn all(client: &Client, elements_per_page: Option<u16>) -> Iter<'_, GameHeader> {
+        client.iter(crate::games::all(&client.client, elements_per_page))
+    }
+
+    pub fn search<'client>(client: &'client Client, name: &str) -> Iter<'client, Game> {
+        client.iter(crate::games::search(&client.client, name))
+    }
+
+    pub fn by_abbreviation(
+        client: &Client,
+        abbreviation: &str,
+        embeds: Embeds,
+    ) -> Result<Game, Error> {
+        client.block_on(crate::games::by_abbreviation(
+            &client.client,
+            abbreviation,
+            embeds,
+        ))
+    }
+
+    pub fn by_id(client: &Client, game_id: GameId, embeds: Embeds) -> Result<Game, Error> {
+        client.block_on(crate::games::by_id(&client.client, game_id, embeds))
+    }
+
+    pub fn list<'client>(client: &'client Client, query: &GameQuery<'_>) -> Iter<'client, Game> {
+        client.iter(crate::games::list(&client.client, query))
+    }
+
+    pub fn list_bulk<'client>(
+        client: &'client Client,
+        query: &GameQuery<'_>,
+    ) -> Iter<'client, GameHeader> {
+        client.iter(crate::games::list_bulk(&client.client, query))
+    }
+
+    pub fn records<'client>(
+        client: &'client Client,
+        game_id: GameId,
+        query: &RecordsQuery,
+    ) -> Iter<'client, Leaderboard> {
+        client.iter(crate::games::records(&client.client, game_id, query))
+    }
+}
+
+pub mod leaderboards {
+    use super::Client;
+    use crate::{
+        common::{CategoryId, GameId, LevelId},
+        leaderboards::{Leaderboard, LeaderboardQuery},
+        Error,
+    };
+
+    pub fn get(
+        client: &Client,
+        game_id: GameId,
+        category_id: CategoryId,
+        query: &LeaderboardQuery<'_>,
+    ) -> Result<Leaderboard, Error> {
+        client.block_on(crate::leaderboards::get(
+            &client.client,
+            game_id,
+            category_id,
+            query,
+        ))
+    }
+
+    pub fn get_level(
+        client: &Client,
+        game_id: GameId,
+        level_id: LevelId,
+        category_id: CategoryId,
+        query: &LeaderboardQuery<'_>,
+    ) -> Result<Leaderboard, Error> {
+        client.block_on(crate::leaderboards::get_level(
+            &client.client,
+            game_id,
+            level_id,
+            category_id,
+            query,
+        ))
+    }
+}
+
+pub mod levels {
+    use super::Client;
+    use crate::{
+        common::{GameId, LevelId},
+        Category, Error, Level, Variable,
+    };
+
+    pub fn for_game(client: &Client, game_id: GameId) -> Result<Vec<Level>, Error> {
+        client.block_on(crate::levels::for_game(&client.client, game_id))
+    }
+
+    pub fn by_id(client: &Client, level_id: LevelId) -> Result<Level, Error> {
+        client.block_on(crate::levels::by_id(&client.client, level_id))
+    }
+
+    pub fn categories(client: &Client, level_id: LevelId) -> Result<Vec<Category>, Error> {
+        client.block_on(crate::levels::categories(&client.client, level_id))
+    }
+
+    pub fn variables(client: &Client, level_id: LevelId) -> Result<Vec<Variable>, Error> {
+        client.block_on(crate::levels::variables(&client.client, level_id))
+    }
+}
+
+pub mod runs {
+    use super::{Client, Iter};
+    use crate::{
+        common::RunId,
+        runs::{Embeds, ListQuery, NewPlayer, NewRun, NewStatus, Run},
+        Error,
+    };
+
+    pub fn by_id(client: &Client, run_id: RunId, embeds: Embeds) -> Result<Run, Error> {
+        client.block_on(crate::runs::by_id(&client.client, run_id, embeds))
+    }
+
+    pub fn list<'client>(client: &'client Client, query: &ListQuery<'_>) -> Iter<'client, Run> {
+        client.iter(crate::runs::list(&client.client, query))
+    }
+
+    pub fn submit(client: &Client, run: &NewRun<'_>) -> Result<Run, Error> {
+        client.block_on(crate::runs::submit(&client.client, run))
+    }
+
+    pub fn set_status(client: &Client, run_id: RunId, status: NewStatus<'_>) -> Result<Run, Error> {
+        client.block_on(crate::runs::set_status(&client.client, run_id, status))
+    }
+
+    pub fn set_players(
+        client: &Client,
+        run_id: RunId,
+        players: &[NewPlayer<'_>],
+    ) -> Result<Run, Error> {
+        client.block_on(crate::runs::set_players(&client.client, run_id, players))
+    }
+
+    pub fn delete(client: &Client, run_id: RunId) -> Result<Run, Error> {
+        client.block_on(crate::runs::delete(&client.client, run_id))
+    }
+}
+
+pub mod series {
+    use super::{Client, Iter};
+    use crate::{
+        common::SeriesId,
+        games::{Game, GameHeader},
+        series::Series,
+        Error,
+    };
+
+    pub fn all(client: &Client, elements_per_page: Option<u16>) -> Iter<'_, Series> {
+        client.iter(crate::series::all(&client.client, elements_per_page))
+    }
+
+    pub fn search<'client>(client: &'client Client, name: &str) -> Iter<'client, Series> {
+        client.iter(crate::series::search(&client.client, name))
+    }
+
+    pub fn by_abbreviation(client: &Client, abbreviation: &str) -> Result<Series, Error> {
+        client.block_on(crate::series::by_abbreviation(&client.client, abbreviation))
+    }
+
+    pub fn by_id(client: &Client, series_id: SeriesId) -> Result<Series, Error> {
+        client.block_on(crate::series::by_id(&client.client, series_id))
+    }
+
+    pub fn games(client: &Client, series_id: SeriesId) -> Iter<'_, Game> {
+        client.iter(crate::series::games(&client.client, series_id))
+    }
+
+    pub fn game_headers(
+        client: &Client,
+        series_id: SeriesId,
+        elements_per_page: Option<u16>,
+    ) -> Iter<'_, GameHeader> {
+        client.iter(crate::series::game_headers(
+            &client.client,
+            series_id,
+            elements_per_page,
+        ))
+    }
+}
+
+pub mod users {
+    use super::{Client, Iter};
+    use crate::{
+        common::UserId,
+        users::{PersonalBest, PersonalBestsQuery, Search, User},
+        Error,
+    };
+
+    pub fn search<'client>(client: &'client Client, search: &Search<'_>) -> Iter<'client, User> {
+        client.iter(crate::users::search(&client.client, search))
+    }
+
+    pub fn by_name(client: &Client, name: &str) -> Result<User, Error> {
+        client.block_on(crate::users::by_name(&client.client, name))
+    }
+
+    pub fn by_id(client: &Client, user_id: UserId) -> Result<User, Error> {
+        client.block_on(crate::users::by_id(&client.client, user_id))
+    }
+
+    pub fn personal_bests(
+        client: &Client,
+        user_id: UserId,
+        query: &PersonalBestsQuery<'_>,
+    ) -> Result<Vec<PersonalBest>, Error> {
+        client.block_on(crate::users::personal_bests(&client.client, user_id, query))
+    }
+}
+
+pub mod variables {
+    use super::Client;
+    use crate::{
+        common::{CategoryId, GameId, LevelId, VariableId},
+        Error, Variable,
+    };
+
+    pub fn by_id(client: &Client, variable_id: VariableId) -> Result<Variable, Error> {
+        client.block_on(crate::variables::by_id(&client.client, variable_id))
+    }
+
+    pub fn for_game(client: &Client, game_id: GameId) -> Result<Vec<Variable>, Error> {
+        client.block_on(crate::variables::for_game(&client.client, game_id))
+    }
+
+    pub fn for_category(client: &Client, category_id: CategoryId) -> Result<Vec<Variable>, Error> {
+        client.block_on(crate::variables::for_category(&client.client, category_id))
+    }
+
+    pub fn for_level(client: &Client, level_id: LevelId) -> Result<Vec<Variable>, Error> {
+        client.block_on(crate::variables::for_level(&client.client, level_id))
+    }
+}
+
+pub mod weblinks {
+    use super::Client;
+    use crate::{
+        weblinks::{Resource, WebLink},
+        Error,
+    };
+
+    /// Resolves the resource the web link refers to.
+    pub fn resolve(client: &Client, web_link: &WebLink) -> Result<Resource, Error> {
+        client.block_on(web_link.resolve(&client.client))
+    }
+}
@@ -0,0 +1,603 @@
+//! Caching of responses. A [`Cache`] stores the responses of `GET` requests by
+//! their URL and API key and answers repeated requests from the store for as
+//! long as the response is fresh. Once a response is stale, it is revalidated
+//! with the server via its `ETag` and `Last-Modified` headers, which avoids
+//! downloading it again if it didn't change.
+
+use crate::{client::API_KEY, send_request, time::Instant, Client, Error};
+use http::{
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    HeaderValue, Request, Response, StatusCode,
+};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::disk::DiskStore;
+
+/// A response stored in a cache.
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    body: Vec<u8>,
+    etag: Option<Box<str>>,
+    last_modified: Option<Box<str>>,
+    expires: Instant,
+}
+
+impl CachedResponse {
+    /// Creates a cached response that stays fresh for the given duration.
+    pub fn new(
+        body: Vec<u8>,
+        etag: Option<Box<str>>,
+        last_modified: Option<Box<str>>,
+        fresh_for: Duration,
+    ) -> Self {
+        Self {
+            body,
+            etag,
+            last_modified,
+            expires: Instant::now() + fresh_for,
+        }
+    }
+
+    /// The body of the response.
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// The `ETag` header of the response, if it had one.
+    pub fn etag(&self) -> Option<&str> {
+        self.etag.as_deref()
+    }
+
+    /// The `Last-Modified` header of the response, if it had one.
+    pub fn last_modified(&self) -> Option<&str> {
+        self.last_modified.as_deref()
+    }
+
+    /// How much longer the response is fresh. This is zero if the response is
+    /// stale.
+    pub fn fresh_for(&self) -> Duration {
+        let now = Instant::now();
+        if self.expires > now {
+            self.expires.duration_since(now)
+        } else {
+            Duration::from_secs(0)
+        }
+    }
+
+    fn is_fresh(&self) -> bool {
+        self.expires > Instant::now()
+    }
+
+    /// Whether the response can be revalidated with the server once it is
+    /// stale.
+    fn is_revalidatable(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+
+    /// Whether the response is still worth keeping at the given time. Stale
+    /// responses are only kept for a while if they can be revalidated.
+    fn is_worth_keeping(&self, now: Instant) -> bool {
+        self.expires > now || (self.is_revalidatable() && self.expires + STALE_RETENTION > now)
+    }
+}
+
+/// How long the stores keep stale responses that can be revalidated.
+const STALE_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// A storage backend for a [`Cache`]. Failing to load or store a response is
+/// not an error, the response is simply requested from the server instead.
+///
+/// The responses are stored by a key, which is the URL of the request. For
+/// authenticated requests, a hash of the API key is appended to the URL, so
+/// responses are never shared between different users.
+pub trait CacheStore: Send + Sync + 'static {
+    /// Loads the response stored for the key, even if it is stale.
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+
+    /// Stores the response for the key, replacing any previous one.
+    fn insert(&self, key: &str, response: CachedResponse);
+}
+
+/// Stores the responses in memory. Stale responses are dropped whenever a
+/// response is stored, unless they can still be revalidated, in which case
+/// they are kept for up to an hour.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    responses: Mutex<HashMap<Box<str>, CachedResponse>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes all the stored responses.
+    pub fn clear(&self) {
+        self.responses.lock().unwrap().clear();
+    }
+}
+
+impl CacheStore for MemoryStore {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut responses = self.responses.lock().unwrap();
+        let response = responses.get(key)?;
+        if !response.is_worth_keeping(Instant::now()) {
+            responses.remove(key);
+            return None;
+        }
+        Some(response.clone())
+    }
+
+    fn insert(&self, key: &str, response: CachedResponse) {
+        let mut responses = self.responses.lock().unwrap();
+        let now = Instant::now();
+        responses.retain(|_, response| response.is_worth_keeping(now));
+        responses.insert(key.into(), response);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod disk {
+    use super::{CacheStore, CachedResponse, STALE_RETENTION};
+    use serde::{Deserialize, Serialize};
+    use std::{
+        borrow::Cow,
+        collections::hash_map::DefaultHasher,
+        fs,
+        hash::{Hash, Hasher},
+        io::{BufRead, BufReader, Write},
+        path::{Path, PathBuf},
+        process,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Mutex,
+        },
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+
+    /// How often the directory is scanned for entries that can be removed.
+    const PRUNE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+    /// Stores the responses as files in a directory, so they outlive the
+    /// process. The directory may be shared between processes. Stale
+    /// responses are removed from time to time, unless they can still be
+    /// revalidated, in which case they are kept for up to an hour.
+    #[derive(Debug)]
+    pub struct DiskStore {
+        directory: PathBuf,
+        last_pruned: Mutex<Option<SystemTime>>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Header<'a> {
+        #[serde(borrow)]
+        key: Cow<'a, str>,
+        #[serde(borrow)]
+        etag: Option<Cow<'a, str>>,
+        #[serde(borrow)]
+        last_modified: Option<Cow<'a, str>>,
+        /// Milliseconds since the Unix epoch.
+        expires: u64,
+        /// The length of the body, so a truncated file isn't mistaken for a
+        /// response.
+        length: u64,
+    }
+
+    impl Header<'_> {
+        fn is_worth_keeping(&self, now: u64) -> bool {
+            let revalidatable = self.etag.is_some() || self.last_modified.is_some();
+            self.expires > now
+                || (revalidatable
+                    && self
+                        .expires
+                        .saturating_add(STALE_RETENTION.as_millis() as u64)
+                        > now)
+        }
+    }
+
+    impl DiskStore {
+        /// Stores the responses in the directory, which is created if it
+        /// doesn't exist yet.
+        pub fn new(directory: impl Into<PathBuf>) -> Self {
+            Self {
+                directory: directory.into(),
+                last_pruned: Mutex::new(None),
+            }
+        }
+
+        fn path(&self, key: &str) -> PathBuf {
+            let mut hasher = DefaultHasher::new();
+            key.hash(&mut hasher);
+            self.directory.join(format!("{:016x}", hasher.finish()))
+        }
+
+        /// A path to write a response to before moving it into place. It is
+        /// unique to each write, so concurrent writes of the same response
+        /// don't mix.
+        fn temp_path(path: &Path) -> PathBuf {
+            static COUNTER: AtomicU64 = AtomicU64::new(0);
+            path.with_extension(format!(
+                "{}-{}.tmp",
+                process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed),
+            ))
+        }
+
+        /// Removes the responses that aren't worth keeping anymore, as well
+        /// as temporary files that were left behind by interrupted writes.
+        fn prune(&self) {
+            let now = SystemTime::now();
+            {
+                let mut last_pruned = self.last_pruned.lock().unwrap();
+                if last_pruned.is_some_and(|last| {
+                    now.duration_since(last)
+                        .map_or(true, |elapsed| elapsed < PRUNE_INTERVAL)
+                }) {
+                    return;
+                }
+                *last_pruned = Some(now);
+            }
+
+            let entries = match fs::read_dir(&self.directory) {
+                Ok(entries) => entries,
+                Err(_) => return,
+            };
+            for entry in entries.filter_map(Result::ok) {
+                let path = entry.path();
+                let remove = if path.extension().is_some_and(|ext| ext == "tmp") {
+                    entry
+                        .metadata()
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| now.duration_since(modified).ok())
+                        .is_some_and(|age| age > STALE_RETENTION)
+                } else {
+                    fs::File::open(&path)
+                        .and_then(|file| {
+                            let mut line = Vec::new();
+                            BufReader::new(file).read_until(b'\n', &mut line)?;
+                            Ok(line)
+                        })
+                        .ok()
+                        .and_then(|line| {
+                            let header: Header<'_> = serde_json::from_slice(&line).ok()?;
+                            Some(!header.is_worth_keeping(unix_millis(now)))
+                        })
+                        .unwrap_or(false)
+                };
+                if remove {
+                    let _ = fs::remove_file(&path);
+                }
+            }
+        }
+    }
+
+    fn unix_millis(time: SystemTime) -> u64 {
+        time.duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_millis() as u64)
+    }
+
+    impl CacheStore for DiskStore {
+        fn get(&self, key: &str) -> Option<CachedResponse> {
+            let path = self.path(key);
+            let file = fs::read(&path).ok()?;
+            // The header is a single line of JSON, followed by the body.
+            let split = file.iter().position(|&b| b == b'\n')?;
+            let header: Header<'_> = serde_json::from_slice(&file[..split]).ok()?;
+            let body = &file[split + 1..];
+            if header.key != key || header.length != body.len() as u64 {
+                return None;
+            }
+            let now = unix_millis(SystemTime::now());
+            if !header.is_worth_keeping(now) {
+                let _ = fs::remove_file(&path);
+                return None;
+            }
+
+            Some(CachedResponse::new(
+                body.to_vec(),
+                header.etag.map(|etag| etag.into()),
+                header.last_modified.map(|date| date.into()),
+                Duration::from_millis(header.expires.saturating_sub(now)),
+            ))
+        }
+
+        fn insert(&self, key: &str, response: CachedResponse) {
+            let header = Header {
+                key: key.into(),
+                etag: response.etag().map(Into::into),
+                last_modified: response.last_modified().map(Into::into),
+                expires: unix_millis(SystemTime::now() + response.fresh_for()),
+                length: response.body().len() as u64,
+            };
+
+            let path = self.path(key);
+            let temp_path = Self::temp_path(&path);
+
+            let result = fs::create_dir_all(&self.directory)
+                .and_then(|_| fs::File::create(&temp_path))
+                .and_then(|mut file| {
+                    serde_json::to_writer(&mut file, &header)?;
+                    file.write_all(b"\n")?;
+                    file.write_all(response.body())
+                })
+                // Renaming replaces the previous file atomically, so a
+                // partially written file is never read.
+                .and_then(|_| fs::rename(&temp_path, &path));
+
+            if result.is_err() {
+                let _ = fs::remove_file(&temp_path);
+            }
+
+            self.prune();
+        }
+    }
+}
+
+/// A cache of the responses of `GET` requests. How long a response stays
+/// fresh depends on the kind of resource that got requested. Cloning it is
+/// cheap, as the store is shared between the clones.
+#[derive(Clone)]
+pub struct Cache {
+    store: Arc<dyn CacheStore>,
+    default_ttl: Duration,
+    ttls: HashMap<Box<str>, Duration>,
+}
+
+impl Cache {
+    /// Creates a cache that uses the given store. Resources that rarely
+    /// change, like platforms and regions, stay fresh for a day, games and
+    /// their categories, levels and variables for an hour, and everything
+    /// else, like leaderboards and runs, for a minute.
+    pub fn new(store: impl CacheStore) -> Self {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let hour = Duration::from_secs(60 * 60);
+
+        let ttls = [
+            ("developers", day),
+            ("engines", day),
+            ("gametypes", day),
+            ("genres", day),
+            ("platforms", day),
+            ("publishers", day),
+            ("regions", day),
+            ("categories", hour),
+            ("games", hour),
+            ("levels", hour),
+            ("series", hour),
+            ("variables", hour),
+        ]
+        .iter()
+        .map(|&(resource, ttl)| (resource.into(), ttl))
+        .collect();
+
+        Self {
+            store: Arc::new(store),
+            default_ttl: Duration::from_secs(60),
+            ttls,
+        }
+    }
+
+    /// Creates a cache that stores the responses in memory.
+    pub fn in_memory() -> Self {
+        Self::new(MemoryStore::new())
+    }
+
+    /// Changes how long the responses for a kind of resource stay fresh. The
+    /// resource is the first segment of the path after the client's base URL,
+    /// like `leaderboards` or `games`.
+    pub fn ttl(mut self, resource: &str, ttl: Duration) -> Self {
+        self.ttls.insert(resource.into(), ttl);
+        self
+    }
+
+    /// Changes how long the responses for resources without a specific time to
+    /// live stay fresh.
+    pub fn default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = ttl;
+        self
+    }
+
+    fn ttl_for(&self, base_url: &str, url: &str) -> Duration {
+        url.strip_prefix(base_url)
+            .and_then(|path| path.split(&['/', '?'][..]).next())
+            .and_then(|resource| self.ttls.get(resource))
+            .copied()
+            .unwrap_or(self.default_ttl)
+    }
+
+    fn store(&self, key: &str, base_url: &str, url: &str, response: &Response<Vec<u8>>) {
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(Into::into)
+        };
+
+        self.store.insert(
+            key,
+            CachedResponse::new(
+                response.body().clone(),
+                header(ETAG),
+                header(LAST_MODIFIED),
+                self.ttl_for(base_url, url),
+            ),
+        );
+    }
+}
+
+/// The key the response to the request is stored by. The API key is hashed, so
+/// it doesn't end up in the store in plain text.
+fn cache_key(url: &str, request: &Request<Vec<u8>>) -> String {
+    match request.headers().get(API_KEY) {
+        Some(api_key) => {
+            let mut hasher = DefaultHasher::new();
+            api_key.as_bytes().hash(&mut hasher);
+            // Request URLs never contain a fragment, so the hash can't be
+            // confused with a part of the URL.
+            format!("{}#{:016x}", url, hasher.finish())
+        }
+        None => url.to_owned(),
+    }
+}
+
+/// Sends a `GET` request, unless a fresh response is cached. Stale responses
+/// are revalidated if possible.
+pub(crate) async fn send_cached_request(
+    client: &Client,
+    cache: &Cache,
+    mut request: Request<Vec<u8>>,
+) -> Result<Response<Vec<u8>>, Error> {
+    let url = request.uri().to_string();
+    let key = cache_key(&url, &request);
+    let cached = cache.store.get(&key);
+
+    if let Some(cached) = &cached {
+        if cached.is_fresh() {
+            return Ok(Response::new(cached.body.clone()));
+        }
+        let headers = request.headers_mut();
+        if let Some(etag) = cached
+            .etag()
+            .and_then(|etag| HeaderValue::from_str(etag).ok())
+        {
+            headers.insert(IF_NONE_MATCH, etag);
+        }
+        if let Some(date) = cached
+            .last_modified()
+            .and_then(|date| HeaderValue::from_str(date).ok())
+        {
+            headers.insert(IF_MODIFIED_SINCE, date);
+        }
+    }
+
+    let response = send_request(client, request).await?;
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+        if let Some(cached) = cached {
+            let response = Response::new(cached.body);
+            cache.store.insert(
+                &key,
+                CachedResponse::new(
+                    response.body().clone(),
+                    cached.etag,
+                    cached.last_modified,
+                    cache.ttl_for(client.base_url().as_str(), &url),
+                ),
+            );
+            return Ok(response);
+        }
+    } else if response.status() == StatusCode::OK {
+        cache.store(&key, client.base_url().as_str(), &url, &response);
+    }
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cache_key, CacheStore, CachedResponse, MemoryStore, API_KEY};
+    use http::Request;
+    use std::time::Duration;
+
+    fn response(etag: Option<&str>, fresh_for: Duration) -> CachedResponse {
+        CachedResponse::new(Vec::new(), etag.map(Into::into), None, fresh_for)
+    }
+
+    #[test]
+    fn stale_responses_are_evicted() {
+        let store = MemoryStore::new();
+        store.insert("stale", response(None, Duration::from_secs(0)));
+        store.insert(
+            "revalidatable",
+            response(Some("\"1\""), Duration::from_secs(0)),
+        );
+        assert!(store.get("stale").is_none());
+
+        store.insert("fresh", response(None, Duration::from_secs(60)));
+        let responses = store.responses.lock().unwrap();
+        assert!(responses.contains_key("fresh"));
+        assert!(responses.contains_key("revalidatable"));
+        assert!(!responses.contains_key("stale"));
+    }
+
+    #[test]
+    fn responses_are_keyed_by_api_key() {
+        let url = "https://www.speedrun.com/api/v1/profile";
+        let request = |api_key: Option<&str>| {
+            let mut request = Request::get(url);
+            if let Some(api_key) = api_key {
+                request = request.header(API_KEY, api_key);
+            }
+            request.body(Vec::new()).unwrap()
+        };
+
+        let anonymous = cache_key(url, &request(None));
+        let first = cache_key(url, &request(Some("first")));
+        let second = cache_key(url, &request(Some("second")));
+        assert_eq!(anonymous, url);
+        assert_ne!(first, anonymous);
+        assert_ne!(first, second);
+        assert!(!first.contains("first"));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn disk_store() {
+        use super::DiskStore;
+        use std::{fs, process};
+
+        let directory =
+            std::env::temp_dir().join(format!("speedrun-com-api-cache-{}", process::id()));
+        let store = DiskStore::new(&directory);
+
+        let fresh = CachedResponse::new(
+            b"{}".to_vec(),
+            Some("\"1\"".into()),
+            None,
+            Duration::from_secs(60),
+        );
+        store.insert("fresh", fresh);
+        let cached = store.get("fresh").unwrap();
+        assert_eq!(cached.body(), b"{}");
+        assert_eq!(cached.etag(), Some("\"1\""));
+        assert!(store.get("missing").is_none());
+
+        // Stale responses that can't be revalidated are removed when read.
+        store.insert("stale", response(None, Duration::from_secs(0)));
+        assert!(store.get("stale").is_none());
+        assert!(store.get("fresh").is_some());
+
+        // A truncated body is not mistaken for a response.
+        let path = fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .next()
+            .unwrap();
+        let file = fs::read(&path).unwrap();
+        fs::write(&path, &file[..file.len() - 1]).unwrap();
+        assert!(store.get("fresh").is_none());
+
+        // No temporary files are left behind.
+        assert!(fs::read_dir(&directory).unwrap().all(|entry| entry
+            .unwrap()
+            .path()
+            .extension()
+            .is_none()));
+
+        // Stale responses are also pruned when storing responses.
+        store.insert("stale", response(None, Duration::from_secs(0)));
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 2);
+        DiskStore::new(&directory).insert("other", response(None, Duration::from_secs(60)));
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 2);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -1,20 +1,46 @@
-use crate::common::Id;
+use crate::common::{CategoryId, GameId};
 use crate::{
-    execute_request,
+    append_embeds, deserialize_embedded, execute_paginated_request, execute_request,
     games::Game,
-    leaderboards::{self, Leaderboard},
-    Client, Error,
+    leaderboards::{self, Leaderboard, LeaderboardQuery, RecordsQuery},
+    variables::{self, Variable},
+    Client, Data, Error,
 };
+use futures_util::stream::Stream;
 use serde::Deserialize;
+use url::Url;
 
 #[derive(Debug, Deserialize)]
 pub struct Category {
-    pub id: Id,
+    pub id: CategoryId,
     pub weblink: Box<str>,
     pub name: Box<str>,
     #[serde(rename = "type")]
     pub kind: CategoryKind,
     pub rules: Option<Box<str>>,
+    #[serde(default, deserialize_with = "deserialize_embedded")]
+    pub game: Option<Data<Game>>,
+    pub variables: Option<Data<Vec<Variable>>>,
+}
+
+bitflags::bitflags! {
+    #[derive(Default)]
+    pub struct Embeds: u8 {
+        const GAME = 1 << 0;
+        const VARIABLES = 1 << 1;
+    }
+}
+
+impl Embeds {
+    pub(crate) fn append_to(self, url: &mut Url) {
+        append_embeds(
+            url,
+            [(Embeds::GAME, "game"), (Embeds::VARIABLES, "variables")]
+                .iter()
+                .filter(|&&(flag, _)| self.contains(flag))
+                .map(|&(_, name)| name),
+        );
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -29,24 +55,62 @@ impl Category {
         &self,
         client: &Client,
         game: &Game,
-        embeds: leaderboards::Embeds,
+        query: &LeaderboardQuery<'_>,
     ) -> Result<Leaderboard, Error> {
-        leaderboards::get(client, &game.id, &self.id, embeds).await
+        leaderboards::get(client, game.id, self.id, query).await
+    }
+
+    pub async fn variables(&self, client: &Client) -> Result<Vec<Variable>, Error> {
+        variables::for_category(client, self.id).await
+    }
+
+    pub fn records<'client>(
+        &self,
+        client: &'client Client,
+        query: &RecordsQuery,
+    ) -> impl Stream<Item = Result<Leaderboard, Error>> + 'client {
+        records(client, self.id, query)
     }
 }
 
-pub async fn for_game(client: &Client, game_id: &str) -> Result<Vec<Category>, Error> {
-    let mut url = api_url!(games);
+pub async fn for_game(
+    client: &Client,
+    game_id: GameId,
+    embeds: Embeds,
+) -> Result<Vec<Category>, Error> {
+    let mut url = api_url!(client, games);
     url.path_segments_mut()
         .unwrap()
-        .extend(&[game_id, "categories"]);
+        .extend(&[game_id.as_str(), "categories"]);
+    embeds.append_to(&mut url);
 
     execute_request(client, url).await
 }
 
-pub async fn by_id(client: &Client, category_id: &str) -> Result<Category, Error> {
-    let mut url = api_url!(categories);
-    url.path_segments_mut().unwrap().push(category_id);
+pub async fn by_id(
+    client: &Client,
+    category_id: CategoryId,
+    embeds: Embeds,
+) -> Result<Category, Error> {
+    let mut url = api_url!(client, categories);
+    url.path_segments_mut().unwrap().push(category_id.as_str());
+    embeds.append_to(&mut url);
 
     execute_request(client, url).await
 }
+
+/// Lists the top runs of every leaderboard of the category. For per-level
+/// categories this is one leaderboard per level.
+pub fn records<'client>(
+    client: &'client Client,
+    category_id: CategoryId,
+    query: &RecordsQuery,
+) -> impl Stream<Item = Result<Leaderboard, Error>> + 'client {
+    let mut url = api_url!(client, categories);
+    url.path_segments_mut()
+        .unwrap()
+        .extend(&[category_id.as_str(), "records"]);
+    query.append_to(&mut url);
+
+    execute_paginated_request(client, url)
+}
@@ -0,0 +1,296 @@
+use crate::{
+    cache::Cache,
+    rate_limit::{Budget, RateLimit, RateLimiter},
+    retry::RetryPolicy,
+    transport::{Transport, TransportError},
+};
+use http::{
+    header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT},
+    Request, Response,
+};
+use snafu::{OptionExt, ResultExt};
+use std::sync::Arc;
+use url::Url;
+
+const DEFAULT_BASE_URL: &str = "https://www.speedrun.com/api/v1/";
+
+// Browsers send their own User-Agent and some of them reject requests that try
+// to override it, so the default is only sent outside of the browser.
+#[cfg(not(target_arch = "wasm32"))]
+const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// The client that is used to send requests to speedrun.com. Cloning it is
+/// cheap, as the underlying transport is shared between the clones.
+#[derive(Clone)]
+pub struct Client {
+    transport: Arc<dyn Transport>,
+    base_url: Url,
+    headers: HeaderMap,
+    rate_limiter: Option<RateLimiter>,
+    retry_policy: Option<RetryPolicy>,
+    cache: Option<Cache>,
+}
+
+#[cfg(any(
+    all(feature = "hyper-transport", not(target_arch = "wasm32")),
+    all(feature = "fetch-transport", target_arch = "wasm32"),
+))]
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Client {
+    /// Creates a new client using the default transport of the target.
+    #[cfg(any(
+        all(feature = "hyper-transport", not(target_arch = "wasm32")),
+        all(feature = "fetch-transport", target_arch = "wasm32"),
+    ))]
+    pub fn new() -> Self {
+        // The default settings are always valid.
+        Self::builder().build().unwrap()
+    }
+
+    /// Creates a new client that sends all of its requests through the given
+    /// transport.
+    pub fn with_transport(transport: impl Transport) -> Self {
+        Self::builder().transport(transport).build().unwrap()
+    }
+
+    /// Creates a builder for configuring the base URL, the headers and the
+    /// transport of a client.
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    /// Authenticates all the requests with the given API key. The API key can
+    /// be found in the user's speedrun.com settings.
+    pub fn with_api_key(mut self, api_key: &str) -> Result<Self, http::header::InvalidHeaderValue> {
+        self.headers.insert(API_KEY, api_key_value(api_key)?);
+        Ok(self)
+    }
+
+    /// Limits the rate at which requests are sent. Requests exceeding the
+    /// limit are delayed until the limiter has enough budget. The limiter is
+    /// shared with all the clones of the client created afterwards.
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limiter = Some(RateLimiter::new(rate_limit));
+        self
+    }
+
+    /// Returns the current budget of the rate limiter, if there is one.
+    pub fn rate_limit_budget(&self) -> Option<Budget> {
+        self.rate_limiter.as_ref().map(RateLimiter::budget)
+    }
+
+    /// Retries `GET` requests that failed for transient reasons according to
+    /// the policy.
+    pub fn with_retry(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Answers `GET` requests from the cache while the cached responses are
+    /// fresh.
+    pub fn with_cache(mut self, cache: Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub(crate) fn cache(&self) -> Option<&Cache> {
+        self.cache.as_ref()
+    }
+
+    pub(crate) fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry_policy.as_ref()
+    }
+
+    /// The URL all the resources of the API are relative to.
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    pub(crate) fn api_url(&self, resource: &str) -> Url {
+        self.base_url.join(resource).unwrap()
+    }
+
+    pub(crate) fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub(crate) async fn request(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> Result<Response<Vec<u8>>, TransportError> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+        self.transport.request(request).await
+    }
+}
+
+pub(crate) const API_KEY: &str = "X-API-Key";
+
+fn api_key_value(api_key: &str) -> Result<HeaderValue, http::header::InvalidHeaderValue> {
+    let mut api_key = HeaderValue::from_str(api_key)?;
+    api_key.set_sensitive(true);
+    Ok(api_key)
+}
+
+#[derive(Debug, snafu::Snafu)]
+pub enum BuildError {
+    /// The base URL is not a valid URL.
+    InvalidBaseUrl { source: url::ParseError },
+    /// The base URL can't have paths appended to it.
+    CannotBeABase,
+    /// The name of a header is invalid.
+    InvalidHeaderName {
+        source: http::header::InvalidHeaderName,
+    },
+    /// The value of a header is invalid.
+    InvalidHeaderValue {
+        source: http::header::InvalidHeaderValue,
+    },
+    /// There is no default transport for the target, so one needs to be
+    /// provided.
+    MissingTransport,
+}
+
+/// A builder for a [`Client`]. Errors in the configuration are reported once
+/// the client is built.
+pub struct ClientBuilder {
+    transport: Option<Arc<dyn Transport>>,
+    base_url: Result<Url, BuildError>,
+    headers: Result<HeaderMap, BuildError>,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClientBuilder {
+    /// Creates a builder that talks to speedrun.com. Outside of the browser,
+    /// it identifies itself as this crate.
+    pub fn new() -> Self {
+        #[allow(unused_mut)]
+        let mut headers = HeaderMap::new();
+        #[cfg(not(target_arch = "wasm32"))]
+        headers.insert(USER_AGENT, HeaderValue::from_static(DEFAULT_USER_AGENT));
+
+        Self {
+            transport: None,
+            base_url: Ok(Url::parse(DEFAULT_BASE_URL).unwrap()),
+            headers: Ok(headers),
+        }
+    }
+
+    /// Sends the requests to a different server, such as a mirror or a mock
+    /// server. The URL corresponds to `https://www.speedrun.com/api/v1/`.
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Url::parse(base_url)
+            .context(InvalidBaseUrl)
+            .and_then(|mut url| {
+                if url.cannot_be_a_base() {
+                    return CannotBeABase.fail();
+                }
+                // Without the trailing slash, the last segment would be
+                // replaced when joining the resources onto the URL.
+                if !url.path().ends_with('/') {
+                    let path = format!("{}/", url.path());
+                    url.set_path(&path);
+                }
+                Ok(url)
+            });
+        self
+    }
+
+    /// Identifies the application via the `User-Agent` header. speedrun.com
+    /// asks for a descriptive User-Agent, so it's best to include the name of
+    /// the application and a way to contact its developers.
+    pub fn user_agent(self, user_agent: &str) -> Self {
+        self.map_headers(|headers| {
+            headers.insert(
+                USER_AGENT,
+                HeaderValue::from_str(user_agent).context(InvalidHeaderValue)?,
+            );
+            Ok(())
+        })
+    }
+
+    /// Authenticates all the requests with the given API key. The API key can
+    /// be found in the user's speedrun.com settings.
+    pub fn api_key(self, api_key: &str) -> Self {
+        self.map_headers(|headers| {
+            headers.insert(API_KEY, api_key_value(api_key).context(InvalidHeaderValue)?);
+            Ok(())
+        })
+    }
+
+    /// Sends the header with every request. Headers added this way are added
+    /// to the ones already configured rather than replacing them.
+    pub fn header(self, name: &str, value: &str) -> Self {
+        self.map_headers(|headers| {
+            headers.append(
+                HeaderName::from_bytes(name.as_bytes()).context(InvalidHeaderName)?,
+                HeaderValue::from_str(value).context(InvalidHeaderValue)?,
+            );
+            Ok(())
+        })
+    }
+
+    /// Sends all the requests through the given transport instead of the
+    /// default transport of the target.
+    pub fn transport(mut self, transport: impl Transport) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Builds the client, reporting the first error in the configuration.
+    pub fn build(self) -> Result<Client, BuildError> {
+        let base_url = self.base_url?;
+        let headers = self.headers?;
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => default_transport().context(MissingTransport)?,
+        };
+
+        Ok(Client {
+            transport,
+            base_url,
+            headers,
+            rate_limiter: None,
+            retry_policy: None,
+            cache: None,
+        })
+    }
+
+    fn map_headers(mut self, f: impl FnOnce(&mut HeaderMap) -> Result<(), BuildError>) -> Self {
+        if let Ok(headers) = &mut self.headers {
+            if let Err(error) = f(headers) {
+                self.headers = Err(error);
+            }
+        }
+        self
+    }
+}
+
+#[cfg(all(feature = "hyper-transport", not(target_arch = "wasm32")))]
+fn default_transport() -> Option<Arc<dyn Transport>> {
+    Some(Arc::new(crate::transport::HyperTransport::new()))
+}
+
+#[cfg(all(feature = "fetch-transport", target_arch = "wasm32"))]
+fn default_transport() -> Option<Arc<dyn Transport>> {
+    Some(Arc::new(crate::transport::FetchTransport::new()))
+}
+
+#[cfg(not(any(
+    all(feature = "hyper-transport", not(target_arch = "wasm32")),
+    all(feature = "fetch-transport", target_arch = "wasm32"),
+)))]
+fn default_transport() -> Option<Arc<dyn Transport>> {
+    None
+}
@@ -1,4 +1,9 @@
-use serde::Deserialize;
+use arrayvec::ArrayString;
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use std::{fmt, marker::PhantomData, str::FromStr};
 
 #[derive(Debug, Deserialize)]
 pub struct Names {
@@ -7,4 +12,176 @@ pub struct Names {
     pub twitch: Option<Box<str>>,
 }
 
-pub type Id = arrayvec::ArrayString<[u8; 8]>;
+#[derive(Debug, snafu::Snafu)]
+pub enum ParseIdError {
+    /// The id is empty.
+    Empty,
+    /// The id is longer than 8 characters.
+    TooLong,
+    /// The id contains characters other than ASCII letters and digits.
+    InvalidCharacter,
+}
+
+macro_rules! ids {
+    ($($(#[$attr:meta])* $name:ident,)*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+            #[serde(transparent)]
+            pub struct $name(ArrayString<[u8; 8]>);
+
+            impl $name {
+                pub fn as_str(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl FromStr for $name {
+                type Err = ParseIdError;
+
+                fn from_str(id: &str) -> Result<Self, ParseIdError> {
+                    if id.is_empty() {
+                        return Err(ParseIdError::Empty);
+                    }
+                    if !id.bytes().all(|b| b.is_ascii_alphanumeric()) {
+                        return Err(ParseIdError::InvalidCharacter);
+                    }
+                    ArrayString::from(id)
+                        .map(Self)
+                        .map_err(|_| ParseIdError::TooLong)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $name {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    deserializer.deserialize_str(IdVisitor::<Self>(PhantomData))
+                }
+            }
+
+            impl AsRef<str> for $name {
+                fn as_ref(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl PartialEq<str> for $name {
+                fn eq(&self, other: &str) -> bool {
+                    self.0.as_str() == other
+                }
+            }
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str(&self.0)
+                }
+            }
+
+            impl fmt::Debug for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Debug::fmt(self.0.as_str(), f)
+                }
+            }
+        )*
+    };
+}
+
+/// Deserializes an id via its `FromStr` implementation, so ids received from
+/// the API follow the same rules as parsed ones.
+struct IdVisitor<T>(PhantomData<T>);
+
+impl<'de, T: FromStr<Err = ParseIdError>> Visitor<'de> for IdVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an id consisting of up to 8 ASCII letters and digits")
+    }
+
+    fn visit_str<E: de::Error>(self, id: &str) -> Result<T, E> {
+        id.parse().map_err(E::custom)
+    }
+}
+
+ids! {
+    /// The id of a game.
+    GameId,
+    /// The id of a category.
+    CategoryId,
+    /// The id of a level.
+    LevelId,
+    /// The id of a run.
+    RunId,
+    /// The id of a user.
+    UserId,
+    /// The id of a variable.
+    VariableId,
+    /// The id of one of the values of a variable.
+    ValueId,
+    /// The id of a platform.
+    PlatformId,
+    /// The id of a region.
+    RegionId,
+    /// The id of a series.
+    SeriesId,
+    /// The id of a game type.
+    GameTypeId,
+    /// The id of a genre.
+    GenreId,
+    /// The id of an engine.
+    EngineId,
+    /// The id of a developer.
+    DeveloperId,
+    /// The id of a publisher.
+    PublisherId,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Direction {
+    Ascending,
+    Descending,
+}
+
+impl Direction {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Direction::Ascending => "asc",
+            Direction::Descending => "desc",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GameId, ParseIdError, UserId};
+    use std::collections::HashMap;
+
+    #[test]
+    fn parse_ids() {
+        assert_eq!("o1y9wo6q".parse::<GameId>().unwrap().as_str(), "o1y9wo6q");
+        assert!(matches!("".parse::<GameId>(), Err(ParseIdError::Empty)));
+        assert!(matches!(
+            "o1y9wo6qx".parse::<GameId>(),
+            Err(ParseIdError::TooLong),
+        ));
+        assert!(matches!(
+            "a-b".parse::<GameId>(),
+            Err(ParseIdError::InvalidCharacter),
+        ));
+        assert!(matches!(
+            "ü".parse::<GameId>(),
+            Err(ParseIdError::InvalidCharacter),
+        ));
+    }
+
+    #[test]
+    fn deserialize_ids() {
+        let id: GameId = serde_json::from_str(r#""o1y9wo6q""#).unwrap();
+        assert_eq!(id, *"o1y9wo6q");
+        for &id in &[r#""""#, r#""o1y9wo6qx""#, r#""a-b""#, "1"] {
+            assert!(serde_json::from_str::<GameId>(id).is_err(), "{}", id);
+        }
+
+        let moderators: HashMap<UserId, bool> =
+            serde_json::from_str(r#"{"zx7gd1yx":true}"#).unwrap();
+        assert!(moderators.contains_key(&"zx7gd1yx".parse().unwrap()));
+    }
+}
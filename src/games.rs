@@ -1,20 +1,39 @@
-use crate::common::Id;
+use crate::common::{
+    DeveloperId, Direction, EngineId, GameId, GameTypeId, GenreId, PlatformId, PublisherId,
+    RegionId, UserId,
+};
 use crate::{
+    append_embeds,
     categories::{self, Category},
-    execute_paginated_request, execute_request, Client, Data, Error,
+    developers::Developer,
+    engines::Engine,
+    execute_paginated_request, execute_request,
+    gametypes::GameType,
+    genres::Genre,
+    leaderboards::{Leaderboard, RecordsQuery},
+    levels::{self, Level},
+    platforms::Platform,
+    publishers::Publisher,
+    regions::Region,
+    users::User,
+    Client, Data, Embeddable, Error,
 };
 use arrayvec::ArrayString;
 use futures_util::stream::Stream;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::fmt::Write;
+use url::Url;
 
 pub use crate::common::Names;
+pub use crate::variables::{
+    Variable, VariableScope, VariableScopeKind, VariableValue, VariableValues,
+};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Game {
-    pub id: Id,
+    pub id: GameId,
     pub names: Names,
     pub abbreviation: Box<str>,
     pub weblink: Box<str>,
@@ -22,10 +41,71 @@ pub struct Game {
     pub release_date: ArrayString<[u8; 10]>,
     pub assets: Assets,
     pub ruleset: Rules,
-    pub platforms: Vec<Id>,
+    pub gametypes: Embeddable<Vec<GameTypeId>, Vec<GameType>>,
+    pub platforms: Embeddable<Vec<PlatformId>, Vec<Platform>>,
+    pub regions: Embeddable<Vec<RegionId>, Vec<Region>>,
+    pub genres: Embeddable<Vec<GenreId>, Vec<Genre>>,
+    pub engines: Embeddable<Vec<EngineId>, Vec<Engine>>,
+    pub developers: Embeddable<Vec<DeveloperId>, Vec<Developer>>,
+    pub publishers: Embeddable<Vec<PublisherId>, Vec<Publisher>>,
+    pub moderators: Embeddable<HashMap<UserId, ModeratorRole>, Vec<User>>,
+    pub levels: Option<Data<Vec<Level>>>,
+    pub categories: Option<Data<Vec<Category>>>,
     pub variables: Option<Data<Vec<Variable>>>,
 }
 
+bitflags::bitflags! {
+    #[derive(Default)]
+    pub struct Embeds: u16 {
+        const LEVELS = 1 << 0;
+        const CATEGORIES = 1 << 1;
+        const MODERATORS = 1 << 2;
+        const GAMETYPES = 1 << 3;
+        const PLATFORMS = 1 << 4;
+        const REGIONS = 1 << 5;
+        const GENRES = 1 << 6;
+        const ENGINES = 1 << 7;
+        const DEVELOPERS = 1 << 8;
+        const PUBLISHERS = 1 << 9;
+        const VARIABLES = 1 << 10;
+    }
+}
+
+impl Embeds {
+    pub(crate) fn append_to(self, url: &mut Url) {
+        append_embeds(
+            url,
+            [
+                (Embeds::LEVELS, "levels"),
+                (Embeds::CATEGORIES, "categories"),
+                (Embeds::MODERATORS, "moderators"),
+                (Embeds::GAMETYPES, "gametypes"),
+                (Embeds::PLATFORMS, "platforms"),
+                (Embeds::REGIONS, "regions"),
+                (Embeds::GENRES, "genres"),
+                (Embeds::ENGINES, "engines"),
+                (Embeds::DEVELOPERS, "developers"),
+                (Embeds::PUBLISHERS, "publishers"),
+                (Embeds::VARIABLES, "variables"),
+            ]
+            .iter()
+            .filter(|&&(flag, _)| self.contains(flag))
+            .map(|&(_, name)| name),
+        );
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ModeratorRole {
+    Moderator,
+    SuperModerator,
+    Verifier,
+    /// A role that isn't known to this crate.
+    #[serde(other)]
+    Other,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Assets {
@@ -61,7 +141,7 @@ pub struct Rules {
     pub emulators_allowed: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize)]
 pub enum TimingMethod {
     #[serde(rename = "realtime")]
     RealTime,
@@ -71,51 +151,210 @@ pub enum TimingMethod {
     InGame,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub struct Variable {
-    pub id: Id,
-    pub name: Box<str>,
-    pub category: Option<Id>,
-    pub scope: VariableScope,
-    pub values: VariableValues,
-    pub mandatory: bool,
-    pub is_subcategory: bool,
+impl TimingMethod {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            TimingMethod::RealTime => "realtime",
+            TimingMethod::RealTimeNoLoads => "realtime_noloads",
+            TimingMethod::InGame => "ingame",
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
-pub struct VariableScope {
-    #[serde(rename = "type")]
-    pub kind: VariableScopeKind,
+pub struct GameHeader {
+    pub id: GameId,
+    pub names: Names,
+    pub abbreviation: Box<str>,
+    pub weblink: Box<str>,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub enum VariableScopeKind {
-    Global,
-    FullGame,
-    AllLevels,
-    SingleLevel,
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OrderBy {
+    InternationalName,
+    JapaneseName,
+    Abbreviation,
+    Released,
+    Created,
+    /// Orders the games by how similar their names are to the name searched
+    /// for. This is the default when searching by name.
+    Similarity,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct VariableValues {
-    pub values: HashMap<Id, VariableValue>,
-    pub default: Option<Id>,
+impl OrderBy {
+    fn as_str(self) -> &'static str {
+        match self {
+            OrderBy::InternationalName => "name.int",
+            OrderBy::JapaneseName => "name.jap",
+            OrderBy::Abbreviation => "abbreviation",
+            OrderBy::Released => "released",
+            OrderBy::Created => "created",
+            OrderBy::Similarity => "similarity",
+        }
+    }
 }
 
-#[derive(Debug, Deserialize)]
-pub struct VariableValue {
-    pub label: Box<str>,
-    pub rules: Option<Box<str>>,
+/// Filters and ordering for listing games. All the filters are combined, so
+/// only games matching every one of them are listed.
+#[derive(Debug, Default, Clone)]
+pub struct GameQuery<'a> {
+    name: Option<&'a str>,
+    abbreviation: Option<&'a str>,
+    released: Option<u16>,
+    gametype: Option<GameTypeId>,
+    platform: Option<PlatformId>,
+    region: Option<RegionId>,
+    genre: Option<GenreId>,
+    engine: Option<EngineId>,
+    developer: Option<DeveloperId>,
+    publisher: Option<PublisherId>,
+    moderator: Option<UserId>,
+    romhack: Option<bool>,
+    order_by: Option<(OrderBy, Option<Direction>)>,
+    elements_per_page: Option<u16>,
+    embeds: Embeds,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct GameHeader {
-    pub id: Id,
-    pub names: Names,
-    pub abbreviation: Box<str>,
-    pub weblink: Box<str>,
+impl<'a> GameQuery<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Performs a fuzzy search for the name of the game.
+    pub fn name(mut self, name: &'a str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Performs an exact match on the abbreviation of the game.
+    pub fn abbreviation(mut self, abbreviation: &'a str) -> Self {
+        self.abbreviation = Some(abbreviation);
+        self
+    }
+
+    /// Only lists games released in the given year.
+    pub fn released(mut self, year: u16) -> Self {
+        self.released = Some(year);
+        self
+    }
+
+    pub fn gametype(mut self, gametype_id: GameTypeId) -> Self {
+        self.gametype = Some(gametype_id);
+        self
+    }
+
+    pub fn platform(mut self, platform_id: PlatformId) -> Self {
+        self.platform = Some(platform_id);
+        self
+    }
+
+    pub fn region(mut self, region_id: RegionId) -> Self {
+        self.region = Some(region_id);
+        self
+    }
+
+    pub fn genre(mut self, genre_id: GenreId) -> Self {
+        self.genre = Some(genre_id);
+        self
+    }
+
+    pub fn engine(mut self, engine_id: EngineId) -> Self {
+        self.engine = Some(engine_id);
+        self
+    }
+
+    pub fn developer(mut self, developer_id: DeveloperId) -> Self {
+        self.developer = Some(developer_id);
+        self
+    }
+
+    pub fn publisher(mut self, publisher_id: PublisherId) -> Self {
+        self.publisher = Some(publisher_id);
+        self
+    }
+
+    pub fn moderator(mut self, user_id: UserId) -> Self {
+        self.moderator = Some(user_id);
+        self
+    }
+
+    /// Lists only romhacks (`true`) or excludes them (`false`).
+    pub fn romhack(mut self, romhack: bool) -> Self {
+        self.romhack = Some(romhack);
+        self
+    }
+
+    /// Orders the games by the given key. If no direction is specified, the
+    /// API's default direction is used, which is ascending.
+    pub fn order_by(mut self, order_by: OrderBy, direction: Option<Direction>) -> Self {
+        self.order_by = Some((order_by, direction));
+        self
+    }
+
+    pub fn elements_per_page(mut self, elements: u16) -> Self {
+        self.elements_per_page = Some(elements);
+        self
+    }
+
+    /// The resources to embed into each game. Embeds are ignored when listing
+    /// the games in bulk mode.
+    pub fn embeds(mut self, embeds: Embeds) -> Self {
+        self.embeds = embeds;
+        self
+    }
+
+    fn append_to(&self, url: &mut Url, bulk: bool) {
+        if !bulk {
+            self.embeds.append_to(url);
+        }
+        let mut pairs = url.query_pairs_mut();
+        for &(key, value) in &[
+            ("name", self.name),
+            ("abbreviation", self.abbreviation),
+            ("gametype", self.gametype.as_ref().map(GameTypeId::as_str)),
+            ("platform", self.platform.as_ref().map(PlatformId::as_str)),
+            ("region", self.region.as_ref().map(RegionId::as_str)),
+            ("genre", self.genre.as_ref().map(GenreId::as_str)),
+            ("engine", self.engine.as_ref().map(EngineId::as_str)),
+            (
+                "developer",
+                self.developer.as_ref().map(DeveloperId::as_str),
+            ),
+            (
+                "publisher",
+                self.publisher.as_ref().map(PublisherId::as_str),
+            ),
+            ("moderator", self.moderator.as_ref().map(UserId::as_str)),
+        ] {
+            if let Some(value) = value {
+                pairs.append_pair(key, value);
+            }
+        }
+        if let Some(released) = self.released {
+            let mut buf = ArrayString::<[u8; 5]>::new();
+            write!(buf, "{}", released).unwrap();
+            pairs.append_pair("released", &buf);
+        }
+        if let Some(romhack) = self.romhack {
+            pairs.append_pair("romhack", if romhack { "true" } else { "false" });
+        }
+        if let Some((order_by, direction)) = self.order_by {
+            pairs.append_pair("orderby", order_by.as_str());
+            if let Some(direction) = direction {
+                pairs.append_pair("direction", direction.as_str());
+            }
+        }
+        if bulk {
+            pairs.append_pair("_bulk", "yes");
+        }
+        let mut buf = ArrayString::<[u8; 5]>::new();
+        if let Some(elements) = self.elements_per_page {
+            write!(buf, "{}", elements).unwrap();
+            pairs.append_pair("max", &buf);
+        } else if bulk {
+            pairs.append_pair("max", "1000");
+        }
+    }
 }
 
 impl Game {
@@ -127,13 +366,25 @@ impl Game {
     }
 
     pub async fn categories(&self, client: &Client) -> Result<Vec<Category>, Error> {
-        categories::for_game(client, &self.id).await
+        categories::for_game(client, self.id, categories::Embeds::empty()).await
+    }
+
+    pub async fn levels(&self, client: &Client) -> Result<Vec<Level>, Error> {
+        levels::for_game(client, self.id).await
+    }
+
+    pub fn records<'client>(
+        &self,
+        client: &'client Client,
+        query: &RecordsQuery,
+    ) -> impl Stream<Item = Result<Leaderboard, Error>> + 'client {
+        records(client, self.id, query)
     }
 }
 
 impl GameHeader {
     pub async fn game(&self, client: &Client) -> Result<Game, Error> {
-        by_id(client, &self.id).await
+        by_id(client, self.id, Embeds::empty()).await
     }
 }
 
@@ -141,7 +392,7 @@ pub fn all(
     client: &Client,
     elements_per_page: Option<u16>,
 ) -> impl Stream<Item = Result<GameHeader, Error>> + '_ {
-    let mut url = api_url!(games);
+    let mut url = api_url!(client, games);
     let mut buf = ArrayString::<[u8; 5]>::new();
     let elements = if let Some(elements) = elements_per_page {
         write!(buf, "{}", elements).unwrap();
@@ -160,15 +411,67 @@ pub fn search<'client>(
     client: &'client Client,
     name: &str,
 ) -> impl Stream<Item = Result<Game, Error>> + 'client {
-    let mut url = api_url!(games);
+    let mut url = api_url!(client, games);
     url.query_pairs_mut().append_pair("name", name);
 
     execute_paginated_request(client, url)
 }
 
-pub async fn by_id(client: &Client, game_id: &str) -> Result<Game, Error> {
-    let mut url = api_url!(games);
-    url.path_segments_mut().unwrap().push(game_id);
+/// Retrieves a game by its abbreviation, as seen in the URL of the game's page
+/// on speedrun.com.
+pub async fn by_abbreviation(
+    client: &Client,
+    abbreviation: &str,
+    embeds: Embeds,
+) -> Result<Game, Error> {
+    let mut url = api_url!(client, games);
+    url.path_segments_mut().unwrap().push(abbreviation);
+    embeds.append_to(&mut url);
+
+    execute_request(client, url).await
+}
+
+pub async fn by_id(client: &Client, game_id: GameId, embeds: Embeds) -> Result<Game, Error> {
+    let mut url = api_url!(client, games);
+    url.path_segments_mut().unwrap().push(game_id.as_str());
+    embeds.append_to(&mut url);
 
     execute_request(client, url).await
 }
+
+pub fn list<'client>(
+    client: &'client Client,
+    query: &GameQuery<'_>,
+) -> impl Stream<Item = Result<Game, Error>> + 'client {
+    let mut url = api_url!(client, games);
+    query.append_to(&mut url, false);
+
+    execute_paginated_request(client, url)
+}
+
+/// Lists the games in bulk mode, which only provides the most basic
+/// information about each game, but allows for up to 1000 games per page.
+pub fn list_bulk<'client>(
+    client: &'client Client,
+    query: &GameQuery<'_>,
+) -> impl Stream<Item = Result<GameHeader, Error>> + 'client {
+    let mut url = api_url!(client, games);
+    query.append_to(&mut url, true);
+
+    execute_paginated_request(client, url)
+}
+
+/// Lists the top runs of every leaderboard of the game.
+pub fn records<'client>(
+    client: &'client Client,
+    game_id: GameId,
+    query: &RecordsQuery,
+) -> impl Stream<Item = Result<Leaderboard, Error>> + 'client {
+    let mut url = api_url!(client, games);
+    url.path_segments_mut()
+        .unwrap()
+        .extend(&[game_id.as_str(), "records"]);
+    query.append_to(&mut url);
+
+    execute_paginated_request(client, url)
+}
@@ -0,0 +1,34 @@
+use crate::{common::GameTypeId, execute_paginated_request, execute_request, Client, Error};
+use arrayvec::ArrayString;
+use futures_util::stream::Stream;
+use serde::Deserialize;
+use std::fmt::Write;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct GameType {
+    pub id: GameTypeId,
+    pub name: Box<str>,
+    pub allows_base_game: bool,
+}
+
+pub fn all(
+    client: &Client,
+    elements_per_page: Option<u16>,
+) -> impl Stream<Item = Result<GameType, Error>> + '_ {
+    let mut url = api_url!(client, gametypes);
+    if let Some(elements) = elements_per_page {
+        let mut buf = ArrayString::<[u8; 5]>::new();
+        write!(buf, "{}", elements).unwrap();
+        url.query_pairs_mut().append_pair("max", &buf);
+    }
+
+    execute_paginated_request(client, url)
+}
+
+pub async fn by_id(client: &Client, gametype_id: GameTypeId) -> Result<GameType, Error> {
+    let mut url = api_url!(client, gametypes);
+    url.path_segments_mut().unwrap().push(gametype_id.as_str());
+
+    execute_request(client, url).await
+}
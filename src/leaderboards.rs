@@ -1,15 +1,257 @@
-use crate::common::Id;
+use crate::categories::Category;
+use crate::common::{CategoryId, GameId, LevelId, PlatformId, RegionId, ValueId, VariableId};
+use crate::games::{Game, TimingMethod};
+use crate::levels::Level;
+use crate::platforms::Platform;
+use crate::regions::Region;
 use crate::runs::{PlayerRef, Players, Run};
-use crate::{execute_request, Client, Data, Error};
+use crate::variables::Variable;
+use crate::{append_embeds, execute_request, Client, Data, Embeddable, Error, Nullable};
 use arrayvec::ArrayString;
 use serde::Deserialize;
+use std::fmt::Write;
+use url::Url;
 
 pub use crate::common::Names;
+pub use crate::users::{Color, NameStyle, User, UserCountry, UserLocation};
 
 bitflags::bitflags! {
     #[derive(Default)]
     pub struct Embeds: u8 {
-        const PLAYERS = 1 << 0;
+        const GAME = 1 << 0;
+        const CATEGORY = 1 << 1;
+        const LEVEL = 1 << 2;
+        const PLAYERS = 1 << 3;
+        const REGIONS = 1 << 4;
+        const PLATFORMS = 1 << 5;
+        const VARIABLES = 1 << 6;
+    }
+}
+
+impl Embeds {
+    pub(crate) fn append_to(self, url: &mut Url) {
+        append_embeds(
+            url,
+            [
+                (Embeds::GAME, "game"),
+                (Embeds::CATEGORY, "category"),
+                (Embeds::LEVEL, "level"),
+                (Embeds::PLAYERS, "players"),
+                (Embeds::REGIONS, "regions"),
+                (Embeds::PLATFORMS, "platforms"),
+                (Embeds::VARIABLES, "variables"),
+            ]
+            .iter()
+            .filter(|&&(flag, _)| self.contains(flag))
+            .map(|&(_, name)| name),
+        );
+    }
+}
+
+/// Selects which leaderboard to retrieve for a category. By default the
+/// leaderboard is retrieved the way the website shows it when first visiting
+/// it.
+#[derive(Debug, Default, Clone)]
+pub struct LeaderboardQuery<'a> {
+    top: Option<u32>,
+    platform: Option<PlatformId>,
+    region: Option<RegionId>,
+    emulators: Option<bool>,
+    video_only: Option<bool>,
+    timing: Option<TimingMethod>,
+    date: Option<&'a str>,
+    variables: Vec<(VariableId, ValueId)>,
+    embeds: Embeds,
+}
+
+impl<'a> LeaderboardQuery<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only returns the top N places. Ties may cause more than N runs to be
+    /// returned.
+    pub fn top(mut self, top: u32) -> Self {
+        self.top = Some(top);
+        self
+    }
+
+    pub fn platform(mut self, platform_id: PlatformId) -> Self {
+        self.platform = Some(platform_id);
+        self
+    }
+
+    pub fn region(mut self, region_id: RegionId) -> Self {
+        self.region = Some(region_id);
+        self
+    }
+
+    /// Only shows runs done on emulators (`true`) or hides them (`false`). If
+    /// not specified, both are shown.
+    pub fn emulators(mut self, emulators: bool) -> Self {
+        self.emulators = Some(emulators);
+        self
+    }
+
+    pub fn video_only(mut self, video_only: bool) -> Self {
+        self.video_only = Some(video_only);
+        self
+    }
+
+    pub fn timing(mut self, timing: TimingMethod) -> Self {
+        self.timing = Some(timing);
+        self
+    }
+
+    /// Retrieves the leaderboard as it was on the given date in the
+    /// `YYYY-MM-DD` format.
+    pub fn date(mut self, date: &'a str) -> Self {
+        self.date = Some(date);
+        self
+    }
+
+    /// Filters the leaderboard by the given value of a variable. This is how
+    /// sub-categories are selected.
+    pub fn variable(mut self, variable_id: VariableId, value_id: ValueId) -> Self {
+        self.variables.push((variable_id, value_id));
+        self
+    }
+
+    pub fn embeds(mut self, embeds: Embeds) -> Self {
+        self.embeds = embeds;
+        self
+    }
+
+    fn append_to(&self, url: &mut Url) {
+        {
+            let mut pairs = url.query_pairs_mut();
+            if let Some(top) = self.top {
+                let mut buf = ArrayString::<[u8; 10]>::new();
+                write!(buf, "{}", top).unwrap();
+                pairs.append_pair("top", &buf);
+            }
+            if let Some(platform) = self.platform {
+                pairs.append_pair("platform", platform.as_str());
+            }
+            if let Some(region) = self.region {
+                pairs.append_pair("region", region.as_str());
+            }
+            if let Some(emulators) = self.emulators {
+                pairs.append_pair("emulators", if emulators { "true" } else { "false" });
+            }
+            if let Some(video_only) = self.video_only {
+                pairs.append_pair("video-only", if video_only { "true" } else { "false" });
+            }
+            if let Some(timing) = self.timing {
+                pairs.append_pair("timing", timing.as_str());
+            }
+            if let Some(date) = self.date {
+                pairs.append_pair("date", date);
+            }
+            for &(variable, value) in &self.variables {
+                pairs.append_pair(&format!("var-{}", variable), value.as_str());
+            }
+        }
+        self.embeds.append_to(url);
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RecordsScope {
+    FullGame,
+    Levels,
+    All,
+}
+
+impl RecordsScope {
+    fn as_str(self) -> &'static str {
+        match self {
+            RecordsScope::FullGame => "full-game",
+            RecordsScope::Levels => "levels",
+            RecordsScope::All => "all",
+        }
+    }
+}
+
+/// Selects which leaderboards to retrieve when querying the records of a game
+/// or category. Each leaderboard only contains the top runs.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct RecordsQuery {
+    top: Option<u32>,
+    scope: Option<RecordsScope>,
+    miscellaneous: Option<bool>,
+    skip_empty: Option<bool>,
+    elements_per_page: Option<u16>,
+    embeds: Embeds,
+}
+
+impl RecordsQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only returns the top N places of each leaderboard. Ties may cause more
+    /// than N runs to be returned. Defaults to 3.
+    pub fn top(mut self, top: u32) -> Self {
+        self.top = Some(top);
+        self
+    }
+
+    /// Whether to retrieve the full game leaderboards, the individual level
+    /// leaderboards or both. Defaults to both.
+    pub fn scope(mut self, scope: RecordsScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    /// Whether to include the leaderboards of miscellaneous categories.
+    /// Defaults to `true`.
+    pub fn miscellaneous(mut self, miscellaneous: bool) -> Self {
+        self.miscellaneous = Some(miscellaneous);
+        self
+    }
+
+    /// Whether to skip leaderboards that don't have any runs. Defaults to
+    /// `false`.
+    pub fn skip_empty(mut self, skip_empty: bool) -> Self {
+        self.skip_empty = Some(skip_empty);
+        self
+    }
+
+    pub fn elements_per_page(mut self, elements: u16) -> Self {
+        self.elements_per_page = Some(elements);
+        self
+    }
+
+    pub fn embeds(mut self, embeds: Embeds) -> Self {
+        self.embeds = embeds;
+        self
+    }
+
+    pub(crate) fn append_to(&self, url: &mut Url) {
+        {
+            let mut pairs = url.query_pairs_mut();
+            if let Some(top) = self.top {
+                let mut buf = ArrayString::<[u8; 10]>::new();
+                write!(buf, "{}", top).unwrap();
+                pairs.append_pair("top", &buf);
+            }
+            if let Some(scope) = self.scope {
+                pairs.append_pair("scope", scope.as_str());
+            }
+            if let Some(miscellaneous) = self.miscellaneous {
+                pairs.append_pair("miscellaneous", if miscellaneous { "yes" } else { "no" });
+            }
+            if let Some(skip_empty) = self.skip_empty {
+                pairs.append_pair("skip-empty", if skip_empty { "true" } else { "false" });
+            }
+            if let Some(elements) = self.elements_per_page {
+                let mut buf = ArrayString::<[u8; 5]>::new();
+                write!(buf, "{}", elements).unwrap();
+                pairs.append_pair("max", &buf);
+            }
+        }
+        self.embeds.append_to(url);
     }
 }
 
@@ -17,10 +259,37 @@ bitflags::bitflags! {
 pub struct Leaderboard {
     pub weblink: Box<str>,
     pub runs: Vec<Record>,
+    pub game: Embeddable<GameId, Game>,
+    pub category: Embeddable<CategoryId, Category>,
+    pub level: Embeddable<Option<LevelId>, Nullable<Level>>,
     pub players: Option<Data<Vec<Player>>>,
+    pub regions: Option<Data<Vec<Region>>>,
+    pub platforms: Option<Data<Vec<Platform>>>,
+    pub variables: Option<Data<Vec<Variable>>>,
 }
 
 impl Leaderboard {
+    pub fn game_id(&self) -> GameId {
+        match &self.game {
+            Embeddable::Id(id) => *id,
+            Embeddable::Embedded(game) => game.data.id,
+        }
+    }
+
+    pub fn category_id(&self) -> CategoryId {
+        match &self.category {
+            Embeddable::Id(id) => *id,
+            Embeddable::Embedded(category) => category.data.id,
+        }
+    }
+
+    pub fn level_id(&self) -> Option<LevelId> {
+        match &self.level {
+            Embeddable::Id(id) => *id,
+            Embeddable::Embedded(level) => level.data.0.as_ref().map(|level| level.id),
+        }
+    }
+
     pub fn records_with_players(
         &self,
     ) -> impl Iterator<Item = (&Record, impl Iterator<Item = PlayerBorrow<'_>>)> {
@@ -29,10 +298,22 @@ impl Leaderboard {
         } else {
             &[]
         };
-        self.runs.iter().map(move |run| {
-            let players = match &run.run.players {
-                Players::Refs(player_refs) => Some(player_refs.iter().filter_map(
-                    move |player_ref| match player_ref {
+        self.runs
+            .iter()
+            .map(move |record| (record, resolve_players(&record.run, db)))
+    }
+}
+
+pub(crate) fn resolve_players<'a>(
+    run: &'a Run,
+    db: &'a [Player],
+) -> impl Iterator<Item = PlayerBorrow<'a>> {
+    let (refs, embedded) = match &run.players {
+        Players::Refs(player_refs) => (
+            Some(
+                player_refs
+                    .iter()
+                    .filter_map(move |player_ref| match player_ref {
                         PlayerRef::User(user_ref) => db.iter().find_map(|player| match player {
                             Player::User(user) if user.id == user_ref.id => {
                                 Some(PlayerBorrow::User(user))
@@ -40,13 +321,15 @@ impl Leaderboard {
                             _ => None,
                         }),
                         PlayerRef::Guest(guest) => Some(PlayerBorrow::Guest(guest)),
-                    },
-                )),
-                Players::Embedded { .. } => None,
-            };
-            (run, players.into_iter().flatten())
-        })
-    }
+                    }),
+            ),
+            None,
+        ),
+        Players::Embedded { data } => (None, Some(data.iter().map(PlayerBorrow::from))),
+    };
+    refs.into_iter()
+        .flatten()
+        .chain(embedded.into_iter().flatten())
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -55,6 +338,15 @@ pub enum PlayerBorrow<'leaderboard> {
     Guest(&'leaderboard Guest),
 }
 
+impl<'leaderboard> From<&'leaderboard Player> for PlayerBorrow<'leaderboard> {
+    fn from(player: &'leaderboard Player) -> Self {
+        match player {
+            Player::User(user) => PlayerBorrow::User(user),
+            Player::Guest(guest) => PlayerBorrow::Guest(guest),
+        }
+    }
+}
+
 impl<'leaderboard> PlayerBorrow<'leaderboard> {
     pub fn name(&self) -> &'leaderboard str {
         match self {
@@ -68,51 +360,15 @@ impl<'leaderboard> PlayerBorrow<'leaderboard> {
 #[serde(tag = "rel")]
 #[serde(rename_all = "kebab-case")]
 pub enum Player {
-    User(User),
+    User(Box<User>),
     Guest(Guest),
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "kebab-case")]
-pub struct User {
-    pub id: Id,
-    pub names: Names,
-    pub weblink: Box<str>,
-    pub name_style: NameStyle,
-    pub location: Option<UserLocation>,
-}
-
 #[derive(Debug, Deserialize)]
 pub struct Guest {
     pub name: Box<str>,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(tag = "style")]
-#[serde(rename_all = "kebab-case")]
-pub enum NameStyle {
-    #[serde(rename_all = "kebab-case")]
-    Solid { color: Color },
-    #[serde(rename_all = "kebab-case")]
-    Gradient { color_from: Color, color_to: Color },
-}
-
-#[derive(Debug, Deserialize)]
-pub struct Color {
-    pub light: Box<str>,
-    pub dark: Box<str>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct UserLocation {
-    pub country: UserCountry,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct UserCountry {
-    pub code: ArrayString<[u8; 6]>, // TODO: Stress Test this
-}
-
 #[derive(Debug, Deserialize)]
 pub struct Record {
     pub place: u32,
@@ -121,27 +377,78 @@ pub struct Record {
 
 pub async fn get(
     client: &Client,
-    game_id: &str,
-    category_id: &str,
-    embeds: Embeds,
+    game_id: GameId,
+    category_id: CategoryId,
+    query: &LeaderboardQuery<'_>,
 ) -> Result<Leaderboard, Error> {
-    let mut url = api_url!(leaderboards);
+    let mut url = api_url!(client, leaderboards);
     url.path_segments_mut()
         .unwrap()
-        .extend(&[game_id, "category", category_id]);
-
-    if !embeds.is_empty() {
-        let mut buf = ArrayString::<[u8; 8]>::new();
-        for &(flag, name) in &[(Embeds::PLAYERS, "players")] {
-            if embeds.contains(flag) {
-                if !buf.is_empty() {
-                    buf.push_str(",");
-                }
-                buf.push_str(name);
-            }
-        }
-        url.query_pairs_mut().append_pair("embed", &buf);
-    }
+        .extend(&[game_id.as_str(), "category", category_id.as_str()]);
+    query.append_to(&mut url);
+
+    execute_request(client, url).await
+}
+
+pub async fn get_level(
+    client: &Client,
+    game_id: GameId,
+    level_id: LevelId,
+    category_id: CategoryId,
+    query: &LeaderboardQuery<'_>,
+) -> Result<Leaderboard, Error> {
+    let mut url = api_url!(client, leaderboards);
+    url.path_segments_mut().unwrap().extend(&[
+        game_id.as_str(),
+        "level",
+        level_id.as_str(),
+        category_id.as_str(),
+    ]);
+    query.append_to(&mut url);
 
     execute_request(client, url).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Leaderboard;
+    use crate::Embeddable;
+
+    fn parse(level: &str) -> Leaderboard {
+        serde_json::from_str(&format!(
+            r#"{{"weblink":"https://www.speedrun.com/sm64","game":"o1y9wo6q","category":"wkpoo02r","runs":[],"level":{}}}"#,
+            level,
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn ids_are_kept() {
+        let leaderboard = parse(r#""xd1rxxrd""#);
+        assert_eq!(leaderboard.game_id().as_str(), "o1y9wo6q");
+        assert_eq!(leaderboard.category_id().as_str(), "wkpoo02r");
+        assert_eq!(leaderboard.level_id().unwrap().as_str(), "xd1rxxrd");
+        assert!(leaderboard.game.embedded().is_none());
+    }
+
+    #[test]
+    fn full_game() {
+        assert!(matches!(parse("null").level, Embeddable::Id(None)));
+        assert!(parse("null").level_id().is_none());
+    }
+
+    #[test]
+    fn full_game_with_embedded_level() {
+        let level = parse(r#"{"data":[]}"#).level;
+        assert!(level.embedded().unwrap().0.is_none());
+    }
+
+    #[test]
+    fn embedded_level() {
+        let leaderboard = parse(
+            r#"{"data":{"id":"xd1rxxrd","name":"Bob-omb Battlefield","weblink":"https://www.speedrun.com/sm64/Bob-omb_Battlefield","rules":null}}"#,
+        );
+        assert_eq!(leaderboard.level_id().unwrap().as_str(), "xd1rxxrd");
+        assert!(leaderboard.level.embedded().unwrap().0.is_some());
+    }
+}
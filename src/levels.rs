@@ -0,0 +1,71 @@
+use crate::common::{GameId, LevelId};
+use crate::{
+    categories::Category,
+    execute_request,
+    games::Game,
+    leaderboards::{self, Leaderboard, LeaderboardQuery},
+    variables::{self, Variable},
+    Client, Error,
+};
+use serde::Deserialize;
+use url::Url;
+
+#[derive(Debug, Deserialize)]
+pub struct Level {
+    pub id: LevelId,
+    pub name: Box<str>,
+    pub weblink: Box<str>,
+    pub rules: Option<Box<str>>,
+}
+
+impl Level {
+    pub async fn categories(&self, client: &Client) -> Result<Vec<Category>, Error> {
+        categories(client, self.id).await
+    }
+
+    pub async fn variables(&self, client: &Client) -> Result<Vec<Variable>, Error> {
+        variables(client, self.id).await
+    }
+
+    pub async fn leaderboard(
+        &self,
+        client: &Client,
+        game: &Game,
+        category: &Category,
+        query: &LeaderboardQuery<'_>,
+    ) -> Result<Leaderboard, Error> {
+        leaderboards::get_level(client, game.id, self.id, category.id, query).await
+    }
+}
+
+fn levels_url(client: &Client, level_id: LevelId) -> Url {
+    let mut url = api_url!(client, levels);
+    url.path_segments_mut().unwrap().push(level_id.as_str());
+    url
+}
+
+pub async fn for_game(client: &Client, game_id: GameId) -> Result<Vec<Level>, Error> {
+    let mut url = api_url!(client, games);
+    url.path_segments_mut()
+        .unwrap()
+        .extend(&[game_id.as_str(), "levels"]);
+
+    execute_request(client, url).await
+}
+
+pub async fn by_id(client: &Client, level_id: LevelId) -> Result<Level, Error> {
+    execute_request(client, levels_url(client, level_id)).await
+}
+
+/// Retrieves the categories that are applicable to the level. These are the
+/// per-level categories of the game.
+pub async fn categories(client: &Client, level_id: LevelId) -> Result<Vec<Category>, Error> {
+    let mut url = levels_url(client, level_id);
+    url.path_segments_mut().unwrap().push("categories");
+
+    execute_request(client, url).await
+}
+
+pub async fn variables(client: &Client, level_id: LevelId) -> Result<Vec<Variable>, Error> {
+    variables::for_level(client, level_id).await
+}
@@ -1,39 +1,54 @@
 use futures_util::stream::{self, Stream};
-use http::{Request, StatusCode};
-use platform::Body;
-use serde::{de::DeserializeOwned, Deserialize};
+use http::{header::CONTENT_TYPE, Method, Request, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 use snafu::ResultExt;
 use url::Url;
 
 macro_rules! api_url {
-    ($resource:ident) => {
-        url::Url::parse(concat!(
-            "https://www.speedrun.com/api/v1/",
-            stringify!($resource)
-        ))
-        .unwrap()
+    ($client:expr, $resource:ident) => {
+        $client.api_url(stringify!($resource))
     };
 }
 
+mod client;
 mod platform;
+mod time;
 
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
+pub mod cache;
 pub mod categories;
 pub mod common;
+pub mod developers;
+pub mod engines;
 pub mod games;
+pub mod gametypes;
+pub mod genres;
 pub mod leaderboards;
+pub mod levels;
 pub mod platforms;
+pub mod publishers;
+pub mod rate_limit;
 pub mod regions;
+pub mod retry;
 pub mod runs;
+pub mod series;
+pub mod transport;
+pub mod users;
+pub mod variables;
+pub mod weblinks;
 
 pub use {
-    categories::Category, games::Game, leaderboards::Leaderboard, platforms::Platform,
-    regions::Region, runs::Run,
+    categories::Category, developers::Developer, engines::Engine, games::Game, gametypes::GameType,
+    genres::Genre, leaderboards::Leaderboard, levels::Level, platforms::Platform,
+    publishers::Publisher, regions::Region, runs::Run, series::Series, users::User,
+    variables::Variable,
 };
 
 #[derive(Debug, snafu::Snafu)]
 pub enum Error {
     /// Failed receiving the response from speedrun.com.
-    Response { source: platform::Error },
+    Response { source: transport::TransportError },
     #[snafu(display("HTTP Status Code: {}", status.canonical_reason().unwrap_or_else(|| status.as_str())))]
     Status { status: StatusCode },
     #[snafu(display("{}", message))]
@@ -43,6 +58,12 @@ pub enum Error {
     },
     /// Failed parsing the response from speedrun.com.
     Json { source: serde_json::Error },
+    /// The resource referenced by the web link doesn't exist.
+    UnresolvedWebLink,
+    /// The request kept failing for transient reasons until it ran out of
+    /// attempts.
+    #[snafu(display("The request failed after {} attempts: {}", attempts, source))]
+    RetriesExhausted { attempts: u32, source: Box<Error> },
 }
 
 #[derive(Deserialize)]
@@ -56,6 +77,78 @@ pub struct Data<T> {
     pub data: T,
 }
 
+/// A resource that is either referenced by its id or, if it got embedded,
+/// provided in its entirety.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Embeddable<I, T> {
+    Id(I),
+    Embedded(Data<T>),
+}
+
+impl<I, T> Embeddable<I, T> {
+    pub fn embedded(&self) -> Option<&T> {
+        match self {
+            Embeddable::Id(_) => None,
+            Embeddable::Embedded(data) => Some(&data.data),
+        }
+    }
+}
+
+/// An embedded resource that may not exist. The API represents a missing
+/// resource as an empty list when it's embedded.
+#[derive(Debug)]
+pub struct Nullable<T>(pub Option<T>);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Nullable<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr<T> {
+            Present(T),
+            Missing([(); 0]),
+        }
+
+        Ok(Nullable(match Repr::deserialize(deserializer)? {
+            Repr::Present(value) => Some(value),
+            Repr::Missing(_) => None,
+        }))
+    }
+}
+
+/// Deserializes a resource that is only available if it got embedded. If it
+/// didn't get embedded, the API either provides its id or `null` instead.
+fn deserialize_embedded<'de, D, T>(deserializer: D) -> Result<Option<Data<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum MaybeEmbedded<T> {
+        Embedded(Data<T>),
+        Id(#[allow(dead_code)] Option<Box<str>>),
+    }
+
+    Ok(match MaybeEmbedded::deserialize(deserializer)? {
+        MaybeEmbedded::Embedded(data) => Some(data),
+        MaybeEmbedded::Id(_) => None,
+    })
+}
+
+fn append_embeds<'a>(url: &mut Url, names: impl IntoIterator<Item = &'a str>) {
+    let mut buf = String::new();
+    for name in names {
+        if !buf.is_empty() {
+            buf.push(',');
+        }
+        buf.push_str(name);
+    }
+    if !buf.is_empty() {
+        url.query_pairs_mut().append_pair("embed", &buf);
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct Page<T> {
     data: Vec<T>,
@@ -64,9 +157,6 @@ struct Page<T> {
 
 #[derive(Debug, Deserialize)]
 struct Pagination {
-    offset: u64,
-    max: u64,
-    size: u64,
     links: Vec<PaginationLink>,
 }
 
@@ -76,37 +166,115 @@ enum PaginationLink {
     #[serde(rename = "next")]
     Next { uri: Box<str> },
     #[serde(rename = "prev")]
-    Previous { uri: Box<str> },
+    Previous,
 }
 
-pub use platform::Client;
+pub use client::{BuildError, Client, ClientBuilder};
+
+fn build_request(
+    client: &Client,
+    method: Method,
+    url: &Url,
+    body: Option<Vec<u8>>,
+) -> Request<Vec<u8>> {
+    let mut builder = Request::builder().method(method).uri(url.as_str());
+    if let Some(headers) = builder.headers_mut() {
+        headers.extend(client.headers().clone());
+    }
+    match body {
+        Some(body) => builder.header(CONTENT_TYPE, "application/json").body(body),
+        None => builder.body(Vec::new()),
+    }
+    .unwrap()
+}
 
 async fn execute_request_without_data<T: DeserializeOwned>(
     client: &Client,
     url: Url,
 ) -> Result<T, Error> {
-    let response = client
-        .request(Request::get(url.as_str()).body(Body::empty()).unwrap())
-        .await
-        .context(Response)?;
-    let status = response.status();
+    execute_built_request(client, build_request(client, Method::GET, &url, None)).await
+}
 
-    if !status.is_success() {
-        if let Ok(reader) = platform::recv_reader(response.into_body()).await {
-            if let Ok(error) = serde_json::from_reader::<_, ApiError>(reader) {
-                return Err(Error::Api {
-                    status,
-                    message: error.message,
-                });
+async fn execute_built_request<T: DeserializeOwned>(
+    client: &Client,
+    request: Request<Vec<u8>>,
+) -> Result<T, Error> {
+    let response = match client.cache() {
+        Some(cache) if request.method() == Method::GET => {
+            cache::send_cached_request(client, cache, request).await?
+        }
+        _ => send_request(client, request).await?,
+    };
+    serde_json::from_slice(response.body()).context(Json)
+}
+
+async fn send_request(
+    client: &Client,
+    request: Request<Vec<u8>>,
+) -> Result<http::Response<Vec<u8>>, Error> {
+    let retry_policy = client
+        .retry_policy()
+        .filter(|_| request.method() == Method::GET);
+    let mut request = Some(request);
+    let mut attempt = 1;
+
+    loop {
+        let attempt_request = match retry_policy {
+            Some(_) => clone_request(request.as_ref().unwrap()),
+            None => request.take().unwrap(),
+        };
+
+        let (error, retry_after) = match client.request(attempt_request).await {
+            Ok(response)
+                if response.status().is_success()
+                    || response.status() == StatusCode::NOT_MODIFIED =>
+            {
+                return Ok(response)
             }
+            Ok(response) => (status_error(&response), retry::retry_after(&response)),
+            Err(source) => (Error::Response { source }, None),
+        };
+
+        match retry_policy {
+            Some(retry_policy) if retry::is_transient(&error) => {
+                if attempt < retry_policy.max_attempts() {
+                    time::sleep(retry_policy.backoff(attempt, retry_after)).await;
+                    attempt += 1;
+                } else if attempt > 1 {
+                    return Err(Error::RetriesExhausted {
+                        attempts: attempt,
+                        source: Box::new(error),
+                    });
+                } else {
+                    return Err(error);
+                }
+            }
+            // Errors that aren't transient are returned as is, even after
+            // retrying, so they can be matched on regardless of what failed
+            // before.
+            _ => return Err(error),
         }
-        return Err(Error::Status { status });
     }
+}
 
-    let reader = platform::recv_reader(response.into_body())
-        .await
-        .context(Response)?;
-    serde_json::from_reader(reader).context(Json)
+fn status_error(response: &http::Response<Vec<u8>>) -> Error {
+    let status = response.status();
+    match serde_json::from_slice::<ApiError>(response.body()) {
+        Ok(error) => Error::Api {
+            status,
+            message: error.message,
+        },
+        Err(_) => Error::Status { status },
+    }
+}
+
+fn clone_request(request: &Request<Vec<u8>>) -> Request<Vec<u8>> {
+    let mut clone = Request::new(request.body().clone());
+    *clone.method_mut() = request.method().clone();
+    *clone.uri_mut() = request.uri().clone();
+    *clone.version_mut() = request.version();
+    *clone.headers_mut() = request.headers().clone();
+    clone
 }
 
 async fn execute_request<T: DeserializeOwned>(client: &Client, url: Url) -> Result<T, Error> {
@@ -114,6 +282,28 @@ async fn execute_request<T: DeserializeOwned>(client: &Client, url: Url) -> Resu
     Ok(data.data)
 }
 
+async fn execute_request_with_body<T: DeserializeOwned, B: Serialize>(
+    client: &Client,
+    method: Method,
+    url: Url,
+    body: &B,
+) -> Result<T, Error> {
+    let body = serde_json::to_vec(body).unwrap();
+    let data: Data<T> =
+        execute_built_request(client, build_request(client, method, &url, Some(body))).await?;
+    Ok(data.data)
+}
+
+async fn execute_request_with_method<T: DeserializeOwned>(
+    client: &Client,
+    method: Method,
+    url: Url,
+) -> Result<T, Error> {
+    let data: Data<T> =
+        execute_built_request(client, build_request(client, method, &url, None)).await?;
+    Ok(data.data)
+}
+
 fn execute_paginated_request<T: DeserializeOwned + 'static>(
     client: &Client,
     url: Url,
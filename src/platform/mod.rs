@@ -1,9 +1,9 @@
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(feature = "hyper-transport", not(target_arch = "wasm32")))]
 mod native;
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(feature = "hyper-transport", not(target_arch = "wasm32")))]
 pub use self::native::*;
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(feature = "fetch-transport", target_arch = "wasm32"))]
 mod wasm;
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(feature = "fetch-transport", target_arch = "wasm32"))]
 pub use self::wasm::*;
@@ -1,26 +1,37 @@
-use bytes::buf::BufExt;
+use crate::transport::{BoxFuture, Transport, TransportError};
 use http::{Request, Response};
+use hyper::{client::HttpConnector, Body};
 use hyper_rustls::HttpsConnector;
-use std::io::Read;
 
-pub use hyper::{Body, Error};
-
-pub async fn recv_reader(body: Body) -> Result<impl Read, Error> {
-    Ok(hyper::body::aggregate(body).await?.reader())
+/// The default transport on native targets, based on hyper and rustls.
+pub struct HyperTransport {
+    client: hyper::Client<HttpsConnector<HttpConnector>>,
 }
 
-pub struct Client {
-    client: hyper::Client<HttpsConnector<hyper::client::HttpConnector>>,
+impl Default for HyperTransport {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl Client {
+impl HyperTransport {
     pub fn new() -> Self {
         let https = HttpsConnector::new();
-        let client = hyper::Client::builder().build::<_, hyper::Body>(https);
+        let client = hyper::Client::builder().build::<_, Body>(https);
         Self { client }
     }
+}
 
-    pub async fn request(&self, request: Request<Body>) -> Result<Response<Body>, Error> {
-        self.client.request(request).await
+impl Transport for HyperTransport {
+    fn request(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> BoxFuture<'_, Result<Response<Vec<u8>>, TransportError>> {
+        let response = self.client.request(request.map(Body::from));
+        Box::pin(async move {
+            let (parts, body) = response.await?.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+            Ok(Response::from_parts(parts, body.to_vec()))
+        })
     }
 }
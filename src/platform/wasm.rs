@@ -1,3 +1,4 @@
+use crate::transport::{BoxFuture, Transport, TransportError};
 use http::{
     header::{HeaderName, HeaderValue},
     request::Parts,
@@ -5,130 +6,131 @@ use http::{
 };
 use js_sys::{Array, Reflect, Uint8Array};
 use snafu::OptionExt;
-use std::io::{Cursor, Read};
 use wasm_bindgen::{prelude::*, JsCast};
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{window, RequestInit};
 
-pub struct Client;
+/// The default transport on the web, based on the `fetch` API.
+#[derive(Default)]
+pub struct FetchTransport {
+    _private: (),
+}
 
 #[derive(Debug, snafu::Snafu)]
 pub enum Error {
     /// There is no global window object to be used.
     NoWindow,
     /// A forbidden header was used.
-    #[snafu(display("A forbidden header was used: {}", error.as_string().unwrap_or_default()))]
-    ForbiddenHeader { error: JsValue },
+    #[snafu(display("A forbidden header was used: {}", message))]
+    ForbiddenHeader { message: String },
     /// Failed to receive the response.
-    #[snafu(display("Failed to receive the response: {}", error.as_string().unwrap_or_default()))]
-    ReceiveResponse { error: JsValue },
-}
-
-pub struct Body {
-    data: Option<Vec<u8>>,
+    #[snafu(display("Failed to receive the response: {}", message))]
+    ReceiveResponse { message: String },
 }
 
-impl Body {
-    pub fn empty() -> Self {
-        Self { data: None }
+fn forbidden_header(error: JsValue) -> Error {
+    Error::ForbiddenHeader {
+        message: error.as_string().unwrap_or_default(),
     }
 }
 
-impl From<Vec<u8>> for Body {
-    fn from(data: Vec<u8>) -> Body {
-        Body { data: Some(data) }
+fn receive_response(error: JsValue) -> Error {
+    Error::ReceiveResponse {
+        message: error.as_string().unwrap_or_default(),
     }
 }
 
-pub async fn recv_reader(body: Body) -> Result<impl Read, Error> {
-    Ok(Cursor::new(body.data.unwrap_or_default()))
-}
-
-impl Client {
+impl FetchTransport {
     pub fn new() -> Self {
-        Client
+        Self { _private: () }
     }
+}
 
-    pub async fn request(&self, request: Request<Body>) -> Result<Response<Body>, Error> {
-        let window = window().context(NoWindow)?;
+impl Transport for FetchTransport {
+    fn request(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> BoxFuture<'_, Result<Response<Vec<u8>>, TransportError>> {
+        Box::pin(async move { Ok(fetch(request).await?) })
+    }
+}
 
-        let (
-            Parts {
-                method,
-                uri,
-                version: _,
-                headers,
-                extensions: _,
-                ..
-            },
-            body,
-        ) = request.into_parts();
+async fn fetch(request: Request<Vec<u8>>) -> Result<Response<Vec<u8>>, Error> {
+    let window = window().context(NoWindow)?;
 
-        let mut request_init = RequestInit::new();
+    let (
+        Parts {
+            method,
+            uri,
+            version: _,
+            headers,
+            extensions: _,
+            ..
+        },
+        body,
+    ) = request.into_parts();
 
-        request_init.method(method.as_str());
+    let mut request_init = RequestInit::new();
 
-        if let Some(body) = &body.data {
-            let view = unsafe { Uint8Array::view(&body) };
-            request_init.body(Some(view.unchecked_ref()));
-        }
+    request_init.method(method.as_str());
 
-        let request_headers = web_sys::Headers::new().unwrap();
+    if !body.is_empty() {
+        let view = unsafe { Uint8Array::view(&body) };
+        request_init.body(Some(view.unchecked_ref()));
+    }
 
-        for (name, value) in &headers {
-            request_headers
-                .append(name.as_str(), value.to_str().unwrap_or(""))
-                .map_err(|error| Error::ForbiddenHeader { error })?;
-        }
+    let request_headers = web_sys::Headers::new().unwrap();
 
-        request_init.headers(request_headers.unchecked_ref());
+    for (name, value) in &headers {
+        request_headers
+            .append(name.as_str(), value.to_str().unwrap_or(""))
+            .map_err(forbidden_header)?;
+    }
 
-        let web_response: web_sys::Response =
-            JsFuture::from(window.fetch_with_str_and_init(&uri.to_string(), &request_init))
-                .await
-                .map_err(|error| Error::ReceiveResponse { error })?
-                .unchecked_into();
+    request_init.headers(request_headers.unchecked_ref());
 
-        // Don't drop this earlier, we unsafely borrow from it for the request.
-        drop(body);
+    let web_response: web_sys::Response =
+        JsFuture::from(window.fetch_with_str_and_init(&uri.to_string(), &request_init))
+            .await
+            .map_err(receive_response)?
+            .unchecked_into();
 
-        let buf: js_sys::ArrayBuffer = JsFuture::from(
-            web_response
-                .array_buffer()
-                .map_err(|error| Error::ReceiveResponse { error })?,
-        )
-        .await
-        .map_err(|error| Error::ReceiveResponse { error })?
-        .unchecked_into();
+    // Don't drop this earlier, we unsafely borrow from it for the request.
+    drop(body);
 
-        let slice = Uint8Array::new(&buf);
-        let mut body: Vec<u8> = vec![0; slice.length() as usize];
-        slice.copy_to(&mut body);
+    let buf: js_sys::ArrayBuffer =
+        JsFuture::from(web_response.array_buffer().map_err(receive_response)?)
+            .await
+            .map_err(receive_response)?
+            .unchecked_into();
 
-        let mut response = Response::new(Body { data: Some(body) });
+    let slice = Uint8Array::new(&buf);
+    let mut body: Vec<u8> = vec![0; slice.length() as usize];
+    slice.copy_to(&mut body);
 
-        *response.status_mut() = StatusCode::from_u16(web_response.status()).unwrap();
+    let mut response = Response::new(body);
 
-        let headers = response.headers_mut();
+    *response.status_mut() = StatusCode::from_u16(web_response.status()).unwrap();
 
-        let prop = "value".into();
+    let headers = response.headers_mut();
 
-        for pair in js_sys::try_iter(&web_response.headers()).unwrap().unwrap() {
-            let array: Array = pair.unwrap().into();
-            let vals = array.values();
+    let prop = "value".into();
 
-            let key = Reflect::get(&vals.next().unwrap(), &prop).unwrap();
-            let value = Reflect::get(&vals.next().unwrap(), &prop).unwrap();
+    for pair in js_sys::try_iter(&web_response.headers()).unwrap().unwrap() {
+        let array: Array = pair.unwrap().into();
+        let vals = array.values();
 
-            let key = key.as_string().unwrap();
-            let value = value.as_string().unwrap();
+        let key = Reflect::get(&vals.next().unwrap(), &prop).unwrap();
+        let value = Reflect::get(&vals.next().unwrap(), &prop).unwrap();
 
-            headers.append(
-                HeaderName::from_bytes(key.as_bytes()).unwrap(),
-                HeaderValue::from_str(&value).unwrap(),
-            );
-        }
+        let key = key.as_string().unwrap();
+        let value = value.as_string().unwrap();
 
-        Ok(response)
+        headers.append(
+            HeaderName::from_bytes(key.as_bytes()).unwrap(),
+            HeaderValue::from_str(&value).unwrap(),
+        );
     }
+
+    Ok(response)
 }
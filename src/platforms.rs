@@ -1,4 +1,4 @@
-use crate::{common::Id, execute_paginated_request, execute_request, Client, Error};
+use crate::{common::PlatformId, execute_paginated_request, execute_request, Client, Error};
 use arrayvec::ArrayString;
 use futures_util::stream::Stream;
 use serde::Deserialize;
@@ -7,7 +7,7 @@ use std::fmt::Write;
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Platform {
-    pub id: Id,
+    pub id: PlatformId,
     pub name: Box<str>,
 }
 
@@ -15,7 +15,7 @@ pub fn all(
     client: &Client,
     elements_per_page: Option<u16>,
 ) -> impl Stream<Item = Result<Platform, Error>> + '_ {
-    let mut url = api_url!(platforms);
+    let mut url = api_url!(client, platforms);
     if let Some(elements) = elements_per_page {
         let mut buf = ArrayString::<[u8; 5]>::new();
         write!(buf, "{}", elements).unwrap();
@@ -25,9 +25,9 @@ pub fn all(
     execute_paginated_request(client, url)
 }
 
-pub async fn by_id(client: &Client, platform_id: &str) -> Result<Platform, Error> {
-    let mut url = api_url!(platforms);
-    url.path_segments_mut().unwrap().push(platform_id);
+pub async fn by_id(client: &Client, platform_id: PlatformId) -> Result<Platform, Error> {
+    let mut url = api_url!(client, platforms);
+    url.path_segments_mut().unwrap().push(platform_id.as_str());
 
     execute_request(client, url).await
 }
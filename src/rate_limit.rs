@@ -0,0 +1,183 @@
+//! Client-side rate limiting. speedrun.com only allows 100 requests per minute,
+//! so a [`Client`](crate::Client) can be configured to delay its requests
+//! instead of running into errors. The limit is shared between all the clones
+//! of a client.
+
+use crate::time::{sleep, Instant};
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// The configuration of a token bucket. Up to `burst` requests may be sent at
+/// once, after which `requests` tokens are refilled every `period`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RateLimit {
+    requests: u32,
+    period: Duration,
+    burst: u32,
+}
+
+impl Default for RateLimit {
+    /// The limit speedrun.com enforces, 100 requests per minute.
+    fn default() -> Self {
+        Self::new(100, Duration::from_secs(60))
+    }
+}
+
+impl RateLimit {
+    /// Allows sending the amount of requests per period. The burst size is
+    /// the amount of requests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the amount of requests or the period are zero.
+    pub fn new(requests: u32, period: Duration) -> Self {
+        assert!(requests != 0 && period != Duration::from_secs(0));
+        Self {
+            requests,
+            period,
+            burst: requests,
+        }
+    }
+
+    /// Changes how many requests may be sent at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the burst size is zero.
+    pub fn burst(mut self, burst: u32) -> Self {
+        assert!(burst != 0);
+        self.burst = burst;
+        self
+    }
+
+    fn tokens_per_second(&self) -> f64 {
+        f64::from(self.requests) / self.period.as_secs_f64()
+    }
+}
+
+/// The current state of a rate limiter.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Budget {
+    /// The amount of requests that can be sent right away.
+    pub remaining: u32,
+    /// The maximum amount of requests that can be sent at once.
+    pub capacity: u32,
+    /// The amount of requests that are currently waiting to be sent.
+    pub queued: u32,
+    /// The time until the next token is refilled. This is zero if the bucket
+    /// is full.
+    pub next_refill: Duration,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    limit: RateLimit,
+    /// Goes negative when requests are waiting for a token, with each of them
+    /// having reserved one.
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.tokens_per_second())
+            .min(f64::from(self.limit.burst));
+        self.updated = now;
+    }
+}
+
+/// A token that got reserved by a request that is still waiting for it. If the
+/// request gets cancelled while waiting, the token is returned to the bucket,
+/// so it doesn't delay the requests queued behind it.
+struct Reservation<'a> {
+    bucket: &'a Mutex<Bucket>,
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.refill(Instant::now());
+        bucket.tokens = (bucket.tokens + 1.0).min(f64::from(bucket.limit.burst));
+    }
+}
+
+/// A token bucket that is shared between all the clones of a client.
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            bucket: Arc::new(Mutex::new(Bucket {
+                limit,
+                tokens: f64::from(limit.burst),
+                updated: Instant::now(),
+            })),
+        }
+    }
+
+    /// Takes a token from the bucket, waiting until one is available.
+    pub async fn acquire(&self) {
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap();
+            bucket.refill(Instant::now());
+            bucket.tokens -= 1.0;
+            if bucket.tokens >= 0.0 {
+                return;
+            }
+            Duration::from_secs_f64(-bucket.tokens / bucket.limit.tokens_per_second())
+        };
+        let reservation = Reservation {
+            bucket: &self.bucket,
+        };
+        sleep(wait).await;
+        // The token is used now that the wait is over.
+        mem::forget(reservation);
+    }
+
+    pub fn budget(&self) -> Budget {
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.refill(Instant::now());
+        let tokens_per_second = bucket.limit.tokens_per_second();
+        let missing = f64::from(bucket.limit.burst) - bucket.tokens;
+        Budget {
+            remaining: bucket.tokens.max(0.0) as u32,
+            capacity: bucket.limit.burst,
+            queued: (-bucket.tokens).ceil().max(0.0) as u32,
+            next_refill: if missing > 0.0 {
+                let fraction = 1.0 - bucket.tokens.rem_euclid(1.0);
+                Duration::from_secs_f64(fraction.min(missing) / tokens_per_second)
+            } else {
+                Duration::from_secs(0)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RateLimit, RateLimiter};
+    use futures_util::future::FutureExt;
+    use std::time::Duration;
+
+    #[test]
+    fn cancelled_requests_return_their_token() {
+        let limiter = RateLimiter::new(RateLimit::new(1, Duration::from_secs(60)));
+        assert_eq!(limiter.acquire().now_or_never(), Some(()));
+
+        // Polling the request once makes it wait for a token, which it then
+        // gives back when it gets dropped.
+        assert_eq!(limiter.acquire().now_or_never(), None);
+
+        let budget = limiter.budget();
+        assert_eq!(budget.remaining, 0);
+        assert_eq!(budget.queued, 0);
+        assert!(budget.next_refill > Duration::from_secs(59));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{common::Id, execute_paginated_request, execute_request, Client, Error};
+use crate::{common::RegionId, execute_paginated_request, execute_request, Client, Error};
 use arrayvec::ArrayString;
 use futures_util::stream::Stream;
 use serde::Deserialize;
@@ -7,7 +7,7 @@ use std::fmt::Write;
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Region {
-    pub id: Id,
+    pub id: RegionId,
     pub name: Box<str>,
 }
 
@@ -15,7 +15,7 @@ pub fn all(
     client: &Client,
     elements_per_page: Option<u16>,
 ) -> impl Stream<Item = Result<Region, Error>> + '_ {
-    let mut url = api_url!(regions);
+    let mut url = api_url!(client, regions);
     if let Some(elements) = elements_per_page {
         let mut buf = ArrayString::<[u8; 5]>::new();
         write!(buf, "{}", elements).unwrap();
@@ -25,9 +25,9 @@ pub fn all(
     execute_paginated_request(client, url)
 }
 
-pub async fn by_id(client: &Client, region_id: &str) -> Result<Region, Error> {
-    let mut url = api_url!(regions);
-    url.path_segments_mut().unwrap().push(region_id);
+pub async fn by_id(client: &Client, region_id: RegionId) -> Result<Region, Error> {
+    let mut url = api_url!(client, regions);
+    url.path_segments_mut().unwrap().push(region_id.as_str());
 
     execute_request(client, url).await
 }
@@ -0,0 +1,186 @@
+//! Retrying requests that failed for transient reasons, such as connection
+//! resets, server errors or exceeding the rate limit. Only `GET` requests are
+//! retried, as they are the only ones that are safe to repeat.
+
+use crate::Error;
+use http::{header::RETRY_AFTER, Response, StatusCode};
+use std::{
+    collections::hash_map::RandomState,
+    hash::BuildHasher,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// Describes how often and how long to wait between attempts. The time waited
+/// doubles with every attempt, up to a maximum, and is randomized so that
+/// clients that failed at the same time don't retry at the same time. If the
+/// response specifies how long to wait via the `Retry-After` header, at least
+/// that long is waited.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+impl RetryPolicy {
+    /// Sends a request at most `max_attempts` times, including the first
+    /// attempt.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the maximum amount of attempts is zero.
+    pub fn new(max_attempts: u32) -> Self {
+        assert!(max_attempts != 0);
+        Self {
+            max_attempts,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+
+    /// Changes how long to wait before the first retry. The default is half a
+    /// second.
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Changes the maximum time to wait between two attempts. The default is
+    /// 30 seconds.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub(crate) fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// The time to wait after the given failed attempt, which starts at 1.
+    pub(crate) fn backoff(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .checked_mul(1 << (attempt - 1).min(31))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff));
+        let jittered = backoff / 2 + backoff.mul_f64(random_fraction() / 2.0);
+        retry_after.map_or(jittered, |retry_after| retry_after.max(jittered))
+    }
+}
+
+/// Whether the error may not occur again when retrying the request.
+pub(crate) fn is_transient(error: &Error) -> bool {
+    match error {
+        Error::Response { .. } => true,
+        Error::Status { status } | Error::Api { status, .. } => {
+            status.is_server_error()
+                || *status == StatusCode::REQUEST_TIMEOUT
+                || *status == StatusCode::TOO_MANY_REQUESTS
+                // speedrun.com uses this to report exceeding the rate limit.
+                || status.as_u16() == 420
+        }
+        _ => false,
+    }
+}
+
+/// Parses the `Retry-After` header. Only the amount of seconds is supported,
+/// not HTTP dates.
+pub(crate) fn retry_after<T>(response: &Response<T>) -> Option<Duration> {
+    let seconds = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    seconds.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// A random number in the range `[0, 1)`. This doesn't need to be of high
+/// quality, it only needs to differ between calls and clients.
+fn random_fraction() -> f64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let hash = RandomState::new().hash_one(COUNTER.fetch_add(1, Ordering::Relaxed));
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_transient, retry_after, RetryPolicy};
+    use crate::Error;
+    use http::{header::RETRY_AFTER, Response, StatusCode};
+    use std::{io, time::Duration};
+
+    #[test]
+    fn backoff_doubles_with_jitter() {
+        let policy = RetryPolicy::new(10)
+            .initial_backoff(Duration::from_secs(1))
+            .max_backoff(Duration::from_secs(8));
+        for &(attempt, expected) in &[(1, 1), (2, 2), (3, 4), (4, 8), (5, 8), (100, 8)] {
+            let expected = Duration::from_secs(expected);
+            let backoff = policy.backoff(attempt, None);
+            assert!(
+                backoff >= expected / 2 && backoff <= expected,
+                "{}: {:?}",
+                attempt,
+                backoff,
+            );
+        }
+    }
+
+    #[test]
+    fn backoff_waits_at_least_retry_after() {
+        let policy = RetryPolicy::new(4).initial_backoff(Duration::from_secs(1));
+        let retry_after = Some(Duration::from_secs(60));
+        assert_eq!(policy.backoff(1, retry_after), Duration::from_secs(60));
+
+        let short = Some(Duration::from_millis(1));
+        assert!(policy.backoff(1, short) >= Duration::from_millis(500));
+    }
+
+    #[test]
+    fn transient_errors() {
+        let status = |code| Error::Status {
+            status: StatusCode::from_u16(code).unwrap(),
+        };
+        for &code in &[500, 502, 503, 408, 420, 429] {
+            assert!(is_transient(&status(code)), "{}", code);
+        }
+        for &code in &[400, 401, 403, 404, 422] {
+            assert!(!is_transient(&status(code)), "{}", code);
+        }
+        assert!(is_transient(&Error::Api {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            message: "Unavailable".into(),
+        }));
+        assert!(is_transient(&Error::Response {
+            source: io::Error::from(io::ErrorKind::ConnectionReset).into(),
+        }));
+        assert!(!is_transient(&Error::UnresolvedWebLink));
+    }
+
+    #[test]
+    fn retry_after_seconds() {
+        let response = |value: Option<&str>| {
+            let mut response = Response::builder();
+            if let Some(value) = value {
+                response = response.header(RETRY_AFTER, value);
+            }
+            response.body(()).unwrap()
+        };
+        assert_eq!(
+            retry_after(&response(Some("120"))),
+            Some(Duration::from_secs(120)),
+        );
+        assert_eq!(
+            retry_after(&response(Some(" 5 "))),
+            Some(Duration::from_secs(5)),
+        );
+        assert_eq!(retry_after(&response(None)), None);
+        assert_eq!(
+            retry_after(&response(Some("Wed, 21 Oct 2015 07:28:00 GMT"))),
+            None,
+        );
+        assert_eq!(retry_after(&response(Some("-1"))), None);
+    }
+}
@@ -1,34 +1,85 @@
 // TODO: Move this to players.rs
 pub use crate::leaderboards::{Guest, Player};
 use crate::{
+    append_embeds,
     categories::{self, Category},
-    common::Id,
-    execute_request,
-    games::{self, Game},
+    common::{
+        CategoryId, Direction, GameId, LevelId, PlatformId, RegionId, RunId, UserId, ValueId,
+        VariableId,
+    },
+    execute_paginated_request, execute_request, execute_request_with_body,
+    execute_request_with_method,
+    games::{self, Game, TimingMethod},
+    levels::{self, Level},
     platforms::{self, Platform},
     regions::{self, Region},
-    Client, Error,
+    users::{self, User},
+    variables::{self, ResolvedValues, Variable},
+    Client, Data, Embeddable, Error, Nullable,
 };
 use arrayvec::ArrayString;
-use serde::Deserialize;
+use futures_util::{
+    future,
+    stream::{Stream, StreamExt},
+};
+use http::Method;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt::Write;
+use std::time::Duration;
 use url::Url;
 
 #[derive(Debug, Deserialize)]
 pub struct Run {
-    pub id: Id,
+    pub id: RunId,
     pub weblink: Box<str>,
-    pub game: Id,
-    pub category: Id,
+    pub game: Embeddable<GameId, Game>,
+    pub level: Embeddable<Option<LevelId>, Nullable<Level>>,
+    pub category: Embeddable<CategoryId, Category>,
     pub videos: Option<Videos>,
     pub comment: Option<Box<str>>,
+    pub status: Status,
     pub players: Players,
     pub date: Option<ArrayString<[u8; 10]>>,
     pub submitted: Option<ArrayString<[u8; 20]>>,
     pub times: Times,
     pub system: RunSystem,
     pub splits: Option<Splits>,
-    pub values: HashMap<Id, Id>,
+    pub values: HashMap<VariableId, ValueId>,
+    pub platform: Option<Data<Platform>>,
+    pub region: Option<Data<Nullable<Region>>>,
+}
+
+bitflags::bitflags! {
+    #[derive(Default)]
+    pub struct Embeds: u8 {
+        const GAME = 1 << 0;
+        const CATEGORY = 1 << 1;
+        const LEVEL = 1 << 2;
+        const PLAYERS = 1 << 3;
+        const REGION = 1 << 4;
+        const PLATFORM = 1 << 5;
+    }
+}
+
+impl Embeds {
+    pub(crate) fn append_to(self, url: &mut Url) {
+        append_embeds(
+            url,
+            [
+                (Embeds::GAME, "game"),
+                (Embeds::CATEGORY, "category"),
+                (Embeds::LEVEL, "level"),
+                (Embeds::PLAYERS, "players"),
+                (Embeds::REGION, "region"),
+                (Embeds::PLATFORM, "platform"),
+            ]
+            .iter()
+            .filter(|&&(flag, _)| self.contains(flag))
+            .map(|&(_, name)| name),
+        );
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -49,14 +100,60 @@ pub enum PlayerRef {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct UserRef {
-    pub id: Id,
+    pub id: UserId,
+}
+
+/// The examination status of a run. The examiner may be missing for runs that
+/// got verified automatically or were examined a long time ago.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "status")]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    New,
+    #[serde(rename_all = "kebab-case")]
+    Verified {
+        #[serde(default, deserialize_with = "deserialize_examiner")]
+        examiner: Option<UserRef>,
+        verify_date: Option<ArrayString<[u8; 20]>>,
+    },
+    Rejected {
+        #[serde(default, deserialize_with = "deserialize_examiner")]
+        examiner: Option<UserRef>,
+        reason: Option<Box<str>>,
+    },
+}
+
+fn deserialize_examiner<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<UserRef>, D::Error> {
+    Ok(Option::<UserId>::deserialize(deserializer)?.map(|id| UserRef { id }))
+}
+
+impl Status {
+    pub fn examiner(&self) -> Option<&UserRef> {
+        match self {
+            Status::New => None,
+            Status::Verified { examiner, .. } | Status::Rejected { examiner, .. } => {
+                examiner.as_ref()
+            }
+        }
+    }
+
+    pub fn matches(&self, filter: StatusFilter) -> bool {
+        matches!(
+            (self, filter),
+            (Status::New, StatusFilter::New)
+                | (Status::Verified { .. }, StatusFilter::Verified)
+                | (Status::Rejected { .. }, StatusFilter::Rejected)
+        )
+    }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct RunSystem {
     pub emulated: bool,
-    pub platform: Id,
-    pub region: Option<Id>,
+    pub platform: PlatformId,
+    pub region: Option<RegionId>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -69,10 +166,146 @@ pub struct Video {
     pub uri: Box<str>,
 }
 
-#[derive(Debug, Deserialize)]
+/// The times of a run for each of the timing methods. The primary time is the
+/// time of the game's default timing method.
+#[derive(Debug, Copy, Clone, Deserialize)]
+#[serde(try_from = "TimesRepr")]
 pub struct Times {
-    pub primary: Box<str>,
-    pub primary_t: f64,
+    pub primary: Duration,
+    pub realtime: Option<Duration>,
+    pub realtime_noloads: Option<Duration>,
+    pub ingame: Option<Duration>,
+    /// The API provides each time both as an ISO 8601 duration and in seconds.
+    /// The durations are used, but if any of them is malformed or disagrees
+    /// with the seconds by a millisecond or more, this is set.
+    pub mismatched: bool,
+}
+
+#[derive(Deserialize)]
+struct TimesRepr {
+    primary: Box<str>,
+    primary_t: f64,
+    realtime: Option<Box<str>>,
+    #[serde(default)]
+    realtime_t: f64,
+    realtime_noloads: Option<Box<str>>,
+    #[serde(default)]
+    realtime_noloads_t: f64,
+    ingame: Option<Box<str>>,
+    #[serde(default)]
+    ingame_t: f64,
+}
+
+impl TryFrom<TimesRepr> for Times {
+    type Error = Box<str>;
+
+    fn try_from(repr: TimesRepr) -> Result<Self, Self::Error> {
+        let mut mismatched = false;
+        let mut parse = |time: &str, seconds: f64| {
+            let (duration, mismatch) = parse_time(time, seconds)?;
+            mismatched |= mismatch;
+            Ok::<_, Box<str>>(duration)
+        };
+
+        let primary = parse(&repr.primary, repr.primary_t)?;
+        let realtime = repr
+            .realtime
+            .as_deref()
+            .map(|time| parse(time, repr.realtime_t))
+            .transpose()?;
+        let realtime_noloads = repr
+            .realtime_noloads
+            .as_deref()
+            .map(|time| parse(time, repr.realtime_noloads_t))
+            .transpose()?;
+        let ingame = repr
+            .ingame
+            .as_deref()
+            .map(|time| parse(time, repr.ingame_t))
+            .transpose()?;
+
+        Ok(Times {
+            primary,
+            realtime,
+            realtime_noloads,
+            ingame,
+            mismatched,
+        })
+    }
+}
+
+/// Parses an ISO 8601 duration such as `PT1H23M45.678S`. The time in seconds
+/// that the API provides alongside it is only used if the duration is
+/// malformed. Also returns whether the two disagree.
+fn parse_time(time: &str, seconds: f64) -> Result<(Duration, bool), Box<str>> {
+    match parse_iso_8601_duration(time) {
+        Some(duration) => Ok((duration, (duration.as_secs_f64() - seconds).abs() >= 1e-3)),
+        None => match Duration::try_from_secs_f64(seconds) {
+            Ok(duration) => Ok((duration, true)),
+            Err(_) => Err(format!("Invalid duration: {}", time).into()),
+        },
+    }
+}
+
+fn parse_digits(digits: &str) -> Option<u64> {
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+fn parse_iso_8601_duration(time: &str) -> Option<Duration> {
+    let rest = time.strip_prefix('P')?;
+    let (days, rest) = match rest.find('T') {
+        // The time designator must be followed by at least one component.
+        Some(index) if index + 1 < rest.len() => (&rest[..index], &rest[index + 1..]),
+        Some(_) => return None,
+        None if !rest.is_empty() => (rest, ""),
+        None => return None,
+    };
+
+    let mut total = Duration::default();
+    if !days.is_empty() {
+        let days = parse_digits(days.strip_suffix('D')?)?;
+        total = total.checked_add(Duration::from_secs(days.checked_mul(24 * 60 * 60)?))?;
+    }
+
+    let mut rest = rest;
+    for &(designator, unit) in &[('H', 60 * 60), ('M', 60)] {
+        if let Some(index) = rest.find(designator) {
+            let value = parse_digits(&rest[..index])?;
+            total = total.checked_add(Duration::from_secs(value.checked_mul(unit)?))?;
+            rest = &rest[index + 1..];
+        }
+    }
+
+    if !rest.is_empty() {
+        let seconds = rest.strip_suffix('S')?;
+        let (whole, fraction) = match seconds.find('.') {
+            Some(index) => (&seconds[..index], &seconds[index + 1..]),
+            None => (seconds, "0"),
+        };
+        total = total.checked_add(Duration::from_secs(parse_digits(whole)?))?;
+        // Anything beyond nanoseconds is truncated.
+        if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let fraction = &fraction[..fraction.len().min(9)];
+        let nanos = parse_digits(fraction)? * 10u64.pow(9 - fraction.len() as u32);
+        total = total.checked_add(Duration::from_nanos(nanos))?;
+    }
+
+    Some(total)
+}
+
+impl Times {
+    pub fn get(&self, timing_method: TimingMethod) -> Option<Duration> {
+        match timing_method {
+            TimingMethod::RealTime => self.realtime,
+            TimingMethod::RealTimeNoLoads => self.realtime_noloads,
+            TimingMethod::InGame => self.ingame,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -80,6 +313,308 @@ pub struct Splits {
     pub uri: Box<str>,
 }
 
+/// A run to be submitted to speedrun.com. Submitting requires the `Client` to
+/// be authenticated with an API key. If no players are specified, the owner of
+/// the API key is the sole player of the run.
+#[derive(Debug, Serialize)]
+pub struct NewRun<'a> {
+    pub category: CategoryId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<LevelId>,
+    /// The date the run was done in the `YYYY-MM-DD` format. Defaults to the
+    /// current date.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<RegionId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<PlatformId>,
+    /// Automatically verifies the run. This is only allowed for moderators of
+    /// the game.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified: Option<bool>,
+    pub times: NewRunTimes,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub players: &'a [NewPlayer<'a>],
+    pub emulated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<&'a str>,
+    /// Either the id or the URL of the splits on splits.io.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub splitsio: Option<&'a str>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub variables: HashMap<VariableId, NewVariableValue<'a>>,
+}
+
+impl<'a> NewRun<'a> {
+    /// Creates a run for the given category with no time set for any of the
+    /// timing methods yet.
+    pub fn new(category: CategoryId) -> Self {
+        Self {
+            category,
+            level: None,
+            date: None,
+            region: None,
+            platform: None,
+            verified: None,
+            times: NewRunTimes::default(),
+            players: &[],
+            emulated: false,
+            video: None,
+            comment: None,
+            splitsio: None,
+            variables: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, Serialize)]
+pub struct NewRunTimes {
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_seconds"
+    )]
+    pub realtime: Option<Duration>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_seconds"
+    )]
+    pub realtime_noloads: Option<Duration>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_seconds"
+    )]
+    pub ingame: Option<Duration>,
+}
+
+fn serialize_seconds<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.unwrap_or_default().as_secs_f64())
+}
+
+#[derive(Debug, Copy, Clone, Serialize)]
+#[serde(tag = "rel")]
+#[serde(rename_all = "kebab-case")]
+pub enum NewPlayer<'a> {
+    User { id: UserId },
+    Guest { name: &'a str },
+}
+
+#[derive(Debug, Copy, Clone, Serialize)]
+#[serde(tag = "type", content = "value")]
+#[serde(rename_all = "kebab-case")]
+pub enum NewVariableValue<'a> {
+    /// The id of one of the variable's values.
+    PreDefined(ValueId),
+    /// A custom value for variables that allow user defined values.
+    UserDefined(&'a str),
+}
+
+#[derive(Serialize)]
+struct SubmitRun<'a> {
+    run: &'a NewRun<'a>,
+}
+
+/// The status to set for a run when examining it. Rejecting a run requires a
+/// reason to be given.
+#[derive(Debug, Copy, Clone, Serialize)]
+#[serde(tag = "status")]
+#[serde(rename_all = "kebab-case")]
+pub enum NewStatus<'a> {
+    Verified,
+    Rejected { reason: &'a str },
+}
+
+#[derive(Serialize)]
+struct ChangeStatus<'a> {
+    status: NewStatus<'a>,
+}
+
+#[derive(Serialize)]
+struct ChangePlayers<'a> {
+    players: &'a [NewPlayer<'a>],
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StatusFilter {
+    New,
+    Verified,
+    Rejected,
+}
+
+impl StatusFilter {
+    fn as_str(self) -> &'static str {
+        match self {
+            StatusFilter::New => "new",
+            StatusFilter::Verified => "verified",
+            StatusFilter::Rejected => "rejected",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OrderBy {
+    Game,
+    Category,
+    Level,
+    Platform,
+    Region,
+    Emulated,
+    Date,
+    Submitted,
+    Status,
+    VerifyDate,
+}
+
+impl OrderBy {
+    fn as_str(self) -> &'static str {
+        match self {
+            OrderBy::Game => "game",
+            OrderBy::Category => "category",
+            OrderBy::Level => "level",
+            OrderBy::Platform => "platform",
+            OrderBy::Region => "region",
+            OrderBy::Emulated => "emulated",
+            OrderBy::Date => "date",
+            OrderBy::Submitted => "submitted",
+            OrderBy::Status => "status",
+            OrderBy::VerifyDate => "verify-date",
+        }
+    }
+}
+
+/// Filters and ordering for listing runs. All the filters are combined, so
+/// only runs matching every one of them are listed.
+#[derive(Debug, Default, Clone)]
+pub struct ListQuery<'a> {
+    user: Option<UserId>,
+    guest: Option<&'a str>,
+    examiner: Option<UserId>,
+    game: Option<GameId>,
+    level: Option<LevelId>,
+    category: Option<CategoryId>,
+    platform: Option<PlatformId>,
+    region: Option<RegionId>,
+    emulated: Option<bool>,
+    status: Option<StatusFilter>,
+    order_by: Option<(OrderBy, Option<Direction>)>,
+    elements_per_page: Option<u16>,
+    embeds: Embeds,
+}
+
+impl<'a> ListQuery<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn user(mut self, user_id: UserId) -> Self {
+        self.user = Some(user_id);
+        self
+    }
+
+    pub fn guest(mut self, guest_name: &'a str) -> Self {
+        self.guest = Some(guest_name);
+        self
+    }
+
+    pub fn examiner(mut self, user_id: UserId) -> Self {
+        self.examiner = Some(user_id);
+        self
+    }
+
+    pub fn game(mut self, game_id: GameId) -> Self {
+        self.game = Some(game_id);
+        self
+    }
+
+    pub fn level(mut self, level_id: LevelId) -> Self {
+        self.level = Some(level_id);
+        self
+    }
+
+    pub fn category(mut self, category_id: CategoryId) -> Self {
+        self.category = Some(category_id);
+        self
+    }
+
+    pub fn platform(mut self, platform_id: PlatformId) -> Self {
+        self.platform = Some(platform_id);
+        self
+    }
+
+    pub fn region(mut self, region_id: RegionId) -> Self {
+        self.region = Some(region_id);
+        self
+    }
+
+    pub fn emulated(mut self, emulated: bool) -> Self {
+        self.emulated = Some(emulated);
+        self
+    }
+
+    pub fn status(mut self, status: StatusFilter) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Orders the runs by the given key. If no direction is specified, the
+    /// API's default direction is used, which is ascending.
+    pub fn order_by(mut self, order_by: OrderBy, direction: Option<Direction>) -> Self {
+        self.order_by = Some((order_by, direction));
+        self
+    }
+
+    pub fn elements_per_page(mut self, elements: u16) -> Self {
+        self.elements_per_page = Some(elements);
+        self
+    }
+
+    pub fn embeds(mut self, embeds: Embeds) -> Self {
+        self.embeds = embeds;
+        self
+    }
+
+    fn append_to(&self, url: &mut Url) {
+        self.embeds.append_to(url);
+        let mut pairs = url.query_pairs_mut();
+        for &(key, value) in &[
+            ("user", self.user.as_ref().map(UserId::as_str)),
+            ("guest", self.guest),
+            ("examiner", self.examiner.as_ref().map(UserId::as_str)),
+            ("game", self.game.as_ref().map(GameId::as_str)),
+            ("level", self.level.as_ref().map(LevelId::as_str)),
+            ("category", self.category.as_ref().map(CategoryId::as_str)),
+            ("platform", self.platform.as_ref().map(PlatformId::as_str)),
+            ("region", self.region.as_ref().map(RegionId::as_str)),
+        ] {
+            if let Some(value) = value {
+                pairs.append_pair(key, value);
+            }
+        }
+        if let Some(emulated) = self.emulated {
+            pairs.append_pair("emulated", if emulated { "yes" } else { "no" });
+        }
+        if let Some(status) = self.status {
+            pairs.append_pair("status", status.as_str());
+        }
+        if let Some((order_by, direction)) = self.order_by {
+            pairs.append_pair("orderby", order_by.as_str());
+            if let Some(direction) = direction {
+                pairs.append_pair("direction", direction.as_str());
+            }
+        }
+        if let Some(elements) = self.elements_per_page {
+            let mut buf = ArrayString::<[u8; 5]>::new();
+            write!(buf, "{}", elements).unwrap();
+            pairs.append_pair("max", &buf);
+        }
+    }
+}
+
 impl Splits {
     pub fn id(&self) -> Option<&str> {
         self.uri.rsplit("/").next()
@@ -87,42 +622,263 @@ impl Splits {
 }
 
 impl Run {
+    pub fn is_verified(&self) -> bool {
+        self.status.matches(StatusFilter::Verified)
+    }
+
+    /// Resolves the variable values of the run into their variables and
+    /// labels. The variables can either be embedded into the game or
+    /// retrieved via `variables::for_game`.
+    pub fn resolve_values<'a>(&'a self, variables: &'a [Variable]) -> ResolvedValues<'a> {
+        variables::resolve_values(&self.values, self.category_id(), self.level_id(), variables)
+    }
+
+    /// Resolves the variable values of the run with the variables embedded
+    /// into the game. Returns `None` if the variables were not embedded.
+    pub fn resolve_values_with_game<'a>(&'a self, game: &'a Game) -> Option<ResolvedValues<'a>> {
+        Some(self.resolve_values(&game.variables.as_ref()?.data))
+    }
+
+    pub fn game_id(&self) -> GameId {
+        match &self.game {
+            Embeddable::Id(id) => *id,
+            Embeddable::Embedded(game) => game.data.id,
+        }
+    }
+
+    pub fn category_id(&self) -> CategoryId {
+        match &self.category {
+            Embeddable::Id(id) => *id,
+            Embeddable::Embedded(category) => category.data.id,
+        }
+    }
+
+    pub fn level_id(&self) -> Option<LevelId> {
+        match &self.level {
+            Embeddable::Id(id) => *id,
+            Embeddable::Embedded(level) => level.data.0.as_ref().map(|level| level.id),
+        }
+    }
+
     pub async fn game(&self, client: &Client) -> Result<Game, Error> {
-        games::by_id(client, &self.game).await
+        games::by_id(client, self.game_id(), games::Embeds::empty()).await
     }
 
     pub async fn category(&self, client: &Client) -> Result<Category, Error> {
-        categories::by_id(client, &self.category).await
+        categories::by_id(client, self.category_id(), categories::Embeds::empty()).await
+    }
+
+    pub async fn level(&self, client: &Client) -> Result<Option<Level>, Error> {
+        Ok(match self.level_id() {
+            Some(level) => Some(levels::by_id(client, level).await?),
+            None => None,
+        })
     }
 
     pub fn splits_id(&self) -> Option<&str> {
         self.splits.as_ref()?.id()
     }
+
+    pub async fn set_status(&self, client: &Client, status: NewStatus<'_>) -> Result<Run, Error> {
+        set_status(client, self.id, status).await
+    }
+
+    pub async fn set_players(
+        &self,
+        client: &Client,
+        players: &[NewPlayer<'_>],
+    ) -> Result<Run, Error> {
+        set_players(client, self.id, players).await
+    }
+
+    pub async fn delete(&self, client: &Client) -> Result<Run, Error> {
+        delete(client, self.id).await
+    }
 }
 
 impl UserRef {
-    // pub async fn user(&self, client: &Client) -> Result<User, Error> {}
+    pub async fn user(&self, client: &Client) -> Result<User, Error> {
+        users::by_id(client, self.id).await
+    }
 }
 
 impl RunSystem {
     pub async fn platform(&self, client: &Client) -> Result<Platform, Error> {
-        platforms::by_id(client, &self.platform).await
+        platforms::by_id(client, self.platform).await
     }
 
     pub async fn region(&self, client: &Client) -> Result<Option<Region>, Error> {
         Ok(match &self.region {
-            Some(region) => Some(regions::by_id(client, region).await?),
+            Some(region) => Some(regions::by_id(client, *region).await?),
             None => None,
         })
     }
 }
 
-fn runs_url(run_id: &str) -> Url {
-    let mut url = api_url!(runs);
-    url.path_segments_mut().unwrap().push(run_id);
+fn runs_url(client: &Client, run_id: RunId) -> Url {
+    let mut url = api_url!(client, runs);
+    url.path_segments_mut().unwrap().push(run_id.as_str());
     url
 }
 
-pub async fn by_id(client: &Client, run_id: &str) -> Result<Run, Error> {
-    execute_request(client, runs_url(run_id)).await
+pub async fn by_id(client: &Client, run_id: RunId, embeds: Embeds) -> Result<Run, Error> {
+    let mut url = runs_url(client, run_id);
+    embeds.append_to(&mut url);
+
+    execute_request(client, url).await
+}
+
+pub fn list<'client>(
+    client: &'client Client,
+    query: &ListQuery<'_>,
+) -> impl Stream<Item = Result<Run, Error>> + 'client {
+    let mut url = api_url!(client, runs);
+    query.append_to(&mut url);
+
+    execute_paginated_request(client, url)
+}
+
+/// Filters a stream of runs, such as the one returned by `list`, by their
+/// status. Errors are passed through. Prefer filtering via `ListQuery::status`
+/// where possible, as that happens on the server.
+pub fn filter_by_status<S>(runs: S, filter: StatusFilter) -> impl Stream<Item = Result<Run, Error>>
+where
+    S: Stream<Item = Result<Run, Error>>,
+{
+    runs.filter(move |run| {
+        future::ready(match run {
+            Ok(run) => run.status.matches(filter),
+            Err(_) => true,
+        })
+    })
+}
+
+pub async fn submit(client: &Client, run: &NewRun<'_>) -> Result<Run, Error> {
+    execute_request_with_body(
+        client,
+        Method::POST,
+        api_url!(client, runs),
+        &SubmitRun { run },
+    )
+    .await
+}
+
+/// Verifies or rejects a run. This requires the `Client` to be authenticated
+/// as a moderator of the run's game.
+pub async fn set_status(
+    client: &Client,
+    run_id: RunId,
+    status: NewStatus<'_>,
+) -> Result<Run, Error> {
+    let mut url = runs_url(client, run_id);
+    url.path_segments_mut().unwrap().push("status");
+
+    execute_request_with_body(client, Method::PUT, url, &ChangeStatus { status }).await
+}
+
+/// Replaces the players of a run. This requires the `Client` to be
+/// authenticated as a moderator of the run's game.
+pub async fn set_players(
+    client: &Client,
+    run_id: RunId,
+    players: &[NewPlayer<'_>],
+) -> Result<Run, Error> {
+    let mut url = runs_url(client, run_id);
+    url.path_segments_mut().unwrap().push("players");
+
+    execute_request_with_body(client, Method::PUT, url, &ChangePlayers { players }).await
+}
+
+/// Deletes a run and returns it one last time. This requires the `Client` to
+/// be authenticated as either the submitter of the run or a moderator of the
+/// run's game.
+pub async fn delete(client: &Client, run_id: RunId) -> Result<Run, Error> {
+    execute_request_with_method(client, Method::DELETE, runs_url(client, run_id)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_iso_8601_duration, Times};
+    use std::time::Duration;
+
+    #[test]
+    fn iso_8601_durations() {
+        assert_eq!(
+            parse_iso_8601_duration("PT0.5S"),
+            Some(Duration::from_millis(500)),
+        );
+        assert_eq!(
+            parse_iso_8601_duration("P1DT2H"),
+            Some(Duration::from_secs(26 * 60 * 60)),
+        );
+        assert_eq!(
+            parse_iso_8601_duration("PT1M"),
+            Some(Duration::from_secs(60)),
+        );
+        assert_eq!(
+            parse_iso_8601_duration("PT1H2M3.400S"),
+            Some(Duration::from_millis(3_723_400)),
+        );
+        assert_eq!(
+            parse_iso_8601_duration("PT1.0123456789S"),
+            Some(Duration::new(1, 12_345_678)),
+        );
+    }
+
+    #[test]
+    fn malformed_iso_8601_durations() {
+        for &time in &[
+            "", "P", "PT", "1H", "T1H", "PT1", "PTS", "PT.5S", "PT1.S", "PT-1S", "PT+1S", "PT1H2",
+            "PT1.5X", "P1H", "PT1M2H", "P1DT",
+        ] {
+            assert_eq!(parse_iso_8601_duration(time), None, "{}", time);
+        }
+    }
+
+    #[test]
+    fn overflowing_iso_8601_durations() {
+        for &time in &[
+            "P213503982334601DT5124095576030431H",
+            "PT18446744073709551615H",
+            "PT18446744073709551615M1M",
+            "P1DT18446744073709551615S",
+        ] {
+            assert_eq!(parse_iso_8601_duration(time), None, "{}", time);
+        }
+        assert_eq!(
+            parse_iso_8601_duration("PT1.000000000000000000000000001S"),
+            Some(Duration::from_secs(1)),
+        );
+    }
+
+    #[test]
+    fn invalid_times_are_errors() {
+        for &times in &[
+            r#"{"primary":"P213503982334601DT5124095576030431H","primary_t":1e30}"#,
+            r#"{"primary":"invalid","primary_t":1e30}"#,
+            r#"{"primary":"invalid","primary_t":-1}"#,
+        ] {
+            assert!(serde_json::from_str::<Times>(times).is_err(), "{}", times);
+        }
+    }
+
+    #[test]
+    fn mismatched_times_are_reported() {
+        let times: Times = serde_json::from_str(
+            r#"{"primary":"PT1H2M3.400S","primary_t":3723,"realtime":"PT1H2M3.400S","realtime_t":3723.4}"#,
+        )
+        .unwrap();
+        assert_eq!(times.primary, Duration::from_millis(3_723_400));
+        assert_eq!(times.realtime, Some(Duration::from_millis(3_723_400)));
+        assert!(times.mismatched);
+
+        let times: Times =
+            serde_json::from_str(r#"{"primary":"PT3.400S","primary_t":3.4}"#).unwrap();
+        assert!(!times.mismatched);
+
+        let times: Times =
+            serde_json::from_str(r#"{"primary":"3.4 seconds","primary_t":3.4}"#).unwrap();
+        assert_eq!(times.primary, Duration::from_millis(3400));
+        assert!(times.mismatched);
+    }
 }
@@ -0,0 +1,109 @@
+use crate::common::{Names, SeriesId, UserId};
+use crate::{
+    execute_paginated_request, execute_request,
+    games::{Assets, Game, GameHeader, ModeratorRole},
+    users::User,
+    Client, Embeddable, Error,
+};
+use arrayvec::ArrayString;
+use futures_util::stream::Stream;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+use url::Url;
+
+#[derive(Debug, Deserialize)]
+pub struct Series {
+    pub id: SeriesId,
+    pub names: Names,
+    pub abbreviation: Box<str>,
+    pub weblink: Box<str>,
+    pub moderators: Embeddable<HashMap<UserId, ModeratorRole>, Vec<User>>,
+    pub created: Option<ArrayString<[u8; 20]>>,
+    pub assets: Assets,
+}
+
+impl Series {
+    pub fn games<'client>(
+        &self,
+        client: &'client Client,
+    ) -> impl Stream<Item = Result<Game, Error>> + 'client {
+        games(client, self.id)
+    }
+}
+
+fn series_url(client: &Client, series_id: SeriesId) -> Url {
+    let mut url = api_url!(client, series);
+    url.path_segments_mut().unwrap().push(series_id.as_str());
+    url
+}
+
+pub fn all(
+    client: &Client,
+    elements_per_page: Option<u16>,
+) -> impl Stream<Item = Result<Series, Error>> + '_ {
+    let mut url = api_url!(client, series);
+    if let Some(elements) = elements_per_page {
+        let mut buf = ArrayString::<[u8; 5]>::new();
+        write!(buf, "{}", elements).unwrap();
+        url.query_pairs_mut().append_pair("max", &buf);
+    }
+
+    execute_paginated_request(client, url)
+}
+
+pub fn search<'client>(
+    client: &'client Client,
+    name: &str,
+) -> impl Stream<Item = Result<Series, Error>> + 'client {
+    let mut url = api_url!(client, series);
+    url.query_pairs_mut().append_pair("name", name);
+
+    execute_paginated_request(client, url)
+}
+
+/// Retrieves a series by its abbreviation, as seen in the URL of the series'
+/// page on speedrun.com.
+pub async fn by_abbreviation(client: &Client, abbreviation: &str) -> Result<Series, Error> {
+    let mut url = api_url!(client, series);
+    url.path_segments_mut().unwrap().push(abbreviation);
+
+    execute_request(client, url).await
+}
+
+pub async fn by_id(client: &Client, series_id: SeriesId) -> Result<Series, Error> {
+    execute_request(client, series_url(client, series_id)).await
+}
+
+pub fn games<'client>(
+    client: &'client Client,
+    series_id: SeriesId,
+) -> impl Stream<Item = Result<Game, Error>> + 'client {
+    let mut url = series_url(client, series_id);
+    url.path_segments_mut().unwrap().push("games");
+
+    execute_paginated_request(client, url)
+}
+
+/// Lists the games of the series in bulk mode, which only provides the most
+/// basic information about each game, but allows for far more games per page.
+pub fn game_headers<'client>(
+    client: &'client Client,
+    series_id: SeriesId,
+    elements_per_page: Option<u16>,
+) -> impl Stream<Item = Result<GameHeader, Error>> + 'client {
+    let mut url = series_url(client, series_id);
+    url.path_segments_mut().unwrap().push("games");
+    let mut buf = ArrayString::<[u8; 5]>::new();
+    let elements = if let Some(elements) = elements_per_page {
+        write!(buf, "{}", elements).unwrap();
+        &buf
+    } else {
+        "1000"
+    };
+    url.query_pairs_mut()
+        .append_pair("_bulk", "yes")
+        .append_pair("max", elements);
+
+    execute_paginated_request(client, url)
+}
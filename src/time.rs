@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::Instant;
+
+/// The web doesn't provide a monotonic clock through `std`, so the time is
+/// queried from JavaScript instead.
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Instant(f64);
+
+#[cfg(target_arch = "wasm32")]
+impl Instant {
+    pub fn now() -> Self {
+        Instant(js_sys::Date::now())
+    }
+
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        Duration::from_secs_f64((self.0 - earlier.0).max(0.0) / 1000.0)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl std::ops::Add<Duration> for Instant {
+    type Output = Instant;
+
+    fn add(self, duration: Duration) -> Instant {
+        Instant(self.0 + duration.as_secs_f64() * 1000.0)
+    }
+}
+
+pub async fn sleep(duration: Duration) {
+    futures_timer::Delay::new(duration).await
+}
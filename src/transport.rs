@@ -0,0 +1,39 @@
+//! The HTTP transport that is used to send the requests to speedrun.com. By
+//! default hyper is used on native targets and `fetch` is used on the web,
+//! but any other HTTP client can be plugged in by implementing [`Transport`]
+//! and passing it to [`Client::with_transport`](crate::Client::with_transport).
+
+use http::{Request, Response};
+use std::{future::Future, pin::Pin};
+
+#[cfg(all(feature = "hyper-transport", not(target_arch = "wasm32")))]
+pub use crate::platform::HyperTransport;
+
+#[cfg(all(feature = "fetch-transport", target_arch = "wasm32"))]
+pub use crate::platform::FetchTransport;
+
+/// The error a [`Transport`] reports when it fails to send a request or to
+/// receive its response.
+pub type TransportError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The future returned by a [`Transport`]. It needs to be [`Send`] on every
+/// target other than the web.
+#[cfg(not(target_arch = "wasm32"))]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// The future returned by a [`Transport`]. It needs to be [`Send`] on every
+/// target other than the web.
+#[cfg(target_arch = "wasm32")]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+/// An HTTP client that is able to send a request and receive its entire
+/// response. Non-successful status codes are not supposed to be reported as
+/// errors, they are handled by the caller.
+pub trait Transport: Send + Sync + 'static {
+    /// Sends the request and receives the response, including its entire
+    /// body.
+    fn request(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> BoxFuture<'_, Result<Response<Vec<u8>>, TransportError>>;
+}
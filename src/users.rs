@@ -0,0 +1,314 @@
+use crate::common::{Names, UserId};
+use crate::leaderboards::{self, PlayerBorrow};
+use crate::runs::{Embeds, Run};
+use crate::{execute_paginated_request, execute_request, Client, Error};
+use arrayvec::ArrayString;
+use futures_util::stream::Stream;
+use serde::Deserialize;
+use std::fmt::Write;
+use url::Url;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct User {
+    pub id: UserId,
+    pub names: Names,
+    pub pronouns: Option<Box<str>>,
+    pub weblink: Box<str>,
+    pub name_style: NameStyle,
+    pub role: Role,
+    pub signup: Option<ArrayString<[u8; 20]>>,
+    pub location: Option<UserLocation>,
+    pub twitch: Option<Link>,
+    pub hitbox: Option<Link>,
+    pub youtube: Option<Link>,
+    pub twitter: Option<Link>,
+    pub speedrunslive: Option<Link>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Banned,
+    User,
+    Trusted,
+    Moderator,
+    Admin,
+    Programmer,
+    ContentModerator,
+    /// A role that isn't known to this crate.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "style")]
+#[serde(rename_all = "kebab-case")]
+pub enum NameStyle {
+    #[serde(rename_all = "kebab-case")]
+    Solid { color: Color },
+    #[serde(rename_all = "kebab-case")]
+    Gradient { color_from: Color, color_to: Color },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Color {
+    pub light: Box<str>,
+    pub dark: Box<str>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserLocation {
+    pub country: UserCountry,
+    pub region: Option<UserRegion>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserCountry {
+    pub code: ArrayString<[u8; 6]>, // TODO: Stress Test this
+    pub names: Names,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserRegion {
+    pub code: ArrayString<[u8; 12]>,
+    pub names: Names,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Link {
+    pub uri: Box<str>,
+}
+
+/// The filters to search for users with. All the filters are combined, so
+/// only users matching every one of them are found.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Search<'a> {
+    lookup: Option<&'a str>,
+    name: Option<&'a str>,
+    twitch: Option<&'a str>,
+    hitbox: Option<&'a str>,
+    speedrunslive: Option<&'a str>,
+}
+
+impl<'a> Search<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Performs a case insensitive exact match on the user's names and social
+    /// accounts. This can't be combined with any of the other filters, so
+    /// adding one of them afterwards replaces the lookup.
+    pub fn lookup(lookup: &'a str) -> Self {
+        Self {
+            lookup: Some(lookup),
+            ..Self::default()
+        }
+    }
+
+    /// Performs a fuzzy search for the user's name.
+    pub fn name(mut self, name: &'a str) -> Self {
+        self.lookup = None;
+        self.name = Some(name);
+        self
+    }
+
+    pub fn twitch(mut self, twitch: &'a str) -> Self {
+        self.lookup = None;
+        self.twitch = Some(twitch);
+        self
+    }
+
+    pub fn hitbox(mut self, hitbox: &'a str) -> Self {
+        self.lookup = None;
+        self.hitbox = Some(hitbox);
+        self
+    }
+
+    pub fn speedrunslive(mut self, speedrunslive: &'a str) -> Self {
+        self.lookup = None;
+        self.speedrunslive = Some(speedrunslive);
+        self
+    }
+
+    fn append_to(&self, url: &mut Url) {
+        let mut pairs = url.query_pairs_mut();
+        for &(key, value) in &[
+            ("lookup", self.lookup),
+            ("name", self.name),
+            ("twitch", self.twitch),
+            ("hitbox", self.hitbox),
+            ("speedrunslive", self.speedrunslive),
+        ] {
+            if let Some(value) = value {
+                pairs.append_pair(key, value);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PersonalBest {
+    pub place: u32,
+    pub run: Run,
+}
+
+/// The filters for querying a user's personal bests.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct PersonalBestsQuery<'a> {
+    top: Option<u32>,
+    series: Option<&'a str>,
+    game: Option<&'a str>,
+    embeds: Embeds,
+}
+
+impl<'a> PersonalBestsQuery<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only returns the personal bests that are placed in the top N of their
+    /// leaderboard.
+    pub fn top(mut self, top: u32) -> Self {
+        self.top = Some(top);
+        self
+    }
+
+    /// Only returns the personal bests of games in the series, given by its id
+    /// or abbreviation.
+    pub fn series(mut self, series: &'a str) -> Self {
+        self.series = Some(series);
+        self
+    }
+
+    /// Only returns the personal bests of the game, given by its id or
+    /// abbreviation.
+    pub fn game(mut self, game: &'a str) -> Self {
+        self.game = Some(game);
+        self
+    }
+
+    pub fn embeds(mut self, embeds: Embeds) -> Self {
+        self.embeds = embeds;
+        self
+    }
+
+    fn append_to(&self, url: &mut Url) {
+        {
+            let mut pairs = url.query_pairs_mut();
+            if let Some(top) = self.top {
+                let mut buf = ArrayString::<[u8; 10]>::new();
+                write!(buf, "{}", top).unwrap();
+                pairs.append_pair("top", &buf);
+            }
+            if let Some(series) = self.series {
+                pairs.append_pair("series", series);
+            }
+            if let Some(game) = self.game {
+                pairs.append_pair("game", game);
+            }
+        }
+        self.embeds.append_to(url);
+    }
+}
+
+impl PersonalBest {
+    /// The players of the run. Users are only included if the players got
+    /// embedded into the runs via `Embeds::PLAYERS`, guests always are.
+    pub fn players(&self) -> impl Iterator<Item = PlayerBorrow<'_>> {
+        leaderboards::resolve_players(&self.run, &[])
+    }
+}
+
+impl User {
+    pub async fn personal_bests(
+        &self,
+        client: &Client,
+        query: &PersonalBestsQuery<'_>,
+    ) -> Result<Vec<PersonalBest>, Error> {
+        personal_bests(client, self.id, query).await
+    }
+}
+
+pub fn search<'client>(
+    client: &'client Client,
+    search: &Search<'_>,
+) -> impl Stream<Item = Result<User, Error>> + 'client {
+    let mut url = api_url!(client, users);
+    search.append_to(&mut url);
+
+    execute_paginated_request(client, url)
+}
+
+/// Retrieves a user by their name, as seen in the URL of the user's profile on
+/// speedrun.com.
+pub async fn by_name(client: &Client, name: &str) -> Result<User, Error> {
+    let mut url = api_url!(client, users);
+    url.path_segments_mut().unwrap().push(name);
+
+    execute_request(client, url).await
+}
+
+pub async fn by_id(client: &Client, user_id: UserId) -> Result<User, Error> {
+    let mut url = api_url!(client, users);
+    url.path_segments_mut().unwrap().push(user_id.as_str());
+
+    execute_request(client, url).await
+}
+
+pub async fn personal_bests(
+    client: &Client,
+    user_id: UserId,
+    query: &PersonalBestsQuery<'_>,
+) -> Result<Vec<PersonalBest>, Error> {
+    let mut url = api_url!(client, users);
+    url.path_segments_mut()
+        .unwrap()
+        .extend(&[user_id.as_str(), "personal-bests"]);
+
+    query.append_to(&mut url);
+
+    execute_request(client, url).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PersonalBestsQuery, Search};
+    use crate::runs::Embeds;
+    use url::Url;
+
+    fn query(search: Search<'_>) -> String {
+        let mut url = Url::parse("https://www.speedrun.com/api/v1/users").unwrap();
+        search.append_to(&mut url);
+        url.query().unwrap_or_default().to_owned()
+    }
+
+    #[test]
+    fn search_filters() {
+        assert_eq!(query(Search::new()), "");
+        assert_eq!(query(Search::lookup("Cheese")), "lookup=Cheese");
+        assert_eq!(
+            query(Search::new().name("che").twitch("cheese05")),
+            "name=che&twitch=cheese05",
+        );
+    }
+
+    #[test]
+    fn lookup_is_not_combined_with_filters() {
+        assert_eq!(
+            query(Search::lookup("Cheese").hitbox("cheese")),
+            "hitbox=cheese",
+        );
+    }
+
+    #[test]
+    fn personal_bests_query() {
+        let mut url = Url::parse("https://www.speedrun.com/api/v1/users").unwrap();
+        PersonalBestsQuery::new()
+            .top(3)
+            .game("sm64")
+            .embeds(Embeds::PLAYERS)
+            .append_to(&mut url);
+        assert_eq!(url.query(), Some("top=3&game=sm64&embed=players"));
+    }
+}
@@ -0,0 +1,441 @@
+use crate::common::{CategoryId, GameId, LevelId, ValueId, VariableId};
+use crate::{categories::Category, execute_request, leaderboards::LeaderboardQuery, Client, Error};
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use std::{collections::HashMap, fmt};
+use url::Url;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Variable {
+    pub id: VariableId,
+    pub name: Box<str>,
+    pub category: Option<CategoryId>,
+    pub scope: VariableScope,
+    pub values: VariableValues,
+    pub mandatory: bool,
+    pub is_subcategory: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VariableScope {
+    #[serde(rename = "type")]
+    pub kind: VariableScopeKind,
+    /// The level the variable is scoped to if it's a single level variable.
+    pub level: Option<LevelId>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VariableScopeKind {
+    Global,
+    FullGame,
+    AllLevels,
+    SingleLevel,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VariableValues {
+    /// The values in the order the website shows them in.
+    #[serde(deserialize_with = "deserialize_in_order")]
+    pub values: Vec<(ValueId, VariableValue)>,
+    pub default: Option<ValueId>,
+}
+
+/// Deserializes a map into a list of its entries, keeping the order they
+/// appear in.
+fn deserialize_in_order<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<(ValueId, VariableValue)>, D::Error> {
+    struct EntriesVisitor;
+
+    impl<'de> Visitor<'de> for EntriesVisitor {
+        type Value = Vec<(ValueId, VariableValue)>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a map of variable values")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut entries = Vec::with_capacity(map.size_hint().unwrap_or_default());
+            while let Some(entry) = map.next_entry()? {
+                entries.push(entry);
+            }
+            Ok(entries)
+        }
+    }
+
+    deserializer.deserialize_map(EntriesVisitor)
+}
+
+impl VariableValues {
+    /// Looks up the value with the given id.
+    pub fn get(&self, value_id: ValueId) -> Option<&VariableValue> {
+        self.values
+            .iter()
+            .find(|(id, _)| *id == value_id)
+            .map(|(_, value)| value)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VariableValue {
+    pub label: Box<str>,
+    pub rules: Option<Box<str>>,
+}
+
+/// A combination of values of sub-category variables. Each sub-category has
+/// its own leaderboard, which is shown as a separate tab on the website.
+#[derive(Debug, Clone)]
+pub struct SubCategory<'a> {
+    pub values: Vec<SubCategoryValue<'a>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct SubCategoryValue<'a> {
+    pub variable: &'a Variable,
+    pub value_id: ValueId,
+    pub value: &'a VariableValue,
+}
+
+/// The values of a run resolved against the variables of its game.
+#[derive(Debug, Clone)]
+pub struct ResolvedValues<'a> {
+    /// The values that could be resolved, with the sub-category values coming
+    /// first. Otherwise they are ordered by the names of their variables.
+    pub values: Vec<SubCategoryValue<'a>>,
+    /// The values that could not be resolved.
+    pub unresolved: Vec<UnresolvedValue<'a>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum UnresolvedValue<'a> {
+    /// The variable is not one of the variables of the game.
+    UnknownVariable {
+        variable_id: VariableId,
+        value_id: ValueId,
+    },
+    /// The value is not one of the variable's values.
+    UnknownValue {
+        variable: &'a Variable,
+        value_id: ValueId,
+    },
+    /// The variable doesn't apply to the run's category or level.
+    OutOfScope {
+        variable: &'a Variable,
+        value_id: ValueId,
+        value: &'a VariableValue,
+    },
+}
+
+impl SubCategoryValue<'_> {
+    pub fn name(&self) -> &str {
+        &self.variable.name
+    }
+
+    pub fn label(&self) -> &str {
+        &self.value.label
+    }
+
+    pub fn is_subcategory(&self) -> bool {
+        self.variable.is_subcategory
+    }
+}
+
+impl Variable {
+    /// Checks whether the variable applies to the leaderboards of the given
+    /// category. The level needs to be specified for per-level categories.
+    pub fn applies_to(&self, category_id: CategoryId, level_id: Option<LevelId>) -> bool {
+        if let Some(variable_category) = &self.category {
+            if *variable_category != category_id {
+                return false;
+            }
+        }
+        match self.scope.kind {
+            VariableScopeKind::Global => true,
+            VariableScopeKind::FullGame => level_id.is_none(),
+            VariableScopeKind::AllLevels => level_id.is_some(),
+            VariableScopeKind::SingleLevel => level_id.is_some() && self.scope.level == level_id,
+        }
+    }
+
+    /// The value that is selected when opening the leaderboard. Variables
+    /// without a default value default to their first value, like on the
+    /// website.
+    pub fn default_value(&self) -> Option<(ValueId, &VariableValue)> {
+        let values = &self.values;
+        values
+            .default
+            .and_then(|default| Some((default, values.get(default)?)))
+            .or_else(|| values.values.first().map(|(id, value)| (*id, value)))
+    }
+}
+
+impl<'a> SubCategory<'a> {
+    /// Checks whether every value of the sub-category is the default value of
+    /// its variable. This is the leaderboard shown when opening the category.
+    pub fn is_default(&self) -> bool {
+        self.values.iter().all(|v| {
+            v.variable
+                .default_value()
+                .is_some_and(|(id, _)| id == v.value_id)
+        })
+    }
+
+    /// The name of the sub-category, consisting of the labels of its values.
+    pub fn name(&self) -> String {
+        let mut name = String::new();
+        for value in &self.values {
+            if !name.is_empty() {
+                name.push_str(", ");
+            }
+            name.push_str(&value.value.label);
+        }
+        name
+    }
+
+    /// Adds the variable filters that select this sub-category to the query.
+    pub fn filter<'q>(&self, mut query: LeaderboardQuery<'q>) -> LeaderboardQuery<'q> {
+        for value in &self.values {
+            query = query.variable(value.variable.id, value.value_id);
+        }
+        query
+    }
+}
+
+/// Enumerates every combination of sub-category values for the leaderboards of
+/// the category, in the order of the tabs on the website. The level needs to
+/// be specified for per-level categories. If there are no sub-category
+/// variables, a single sub-category without any values is returned.
+pub fn sub_categories<'a>(
+    category: &Category,
+    level_id: Option<LevelId>,
+    variables: &'a [Variable],
+) -> Vec<SubCategory<'a>> {
+    let mut combinations = vec![Vec::new()];
+    for variable in variables {
+        if !variable.is_subcategory
+            || variable.values.values.is_empty()
+            || !variable.applies_to(category.id, level_id)
+        {
+            continue;
+        }
+        combinations = combinations
+            .into_iter()
+            .flat_map(|combination: Vec<SubCategoryValue<'a>>| {
+                variable
+                    .values
+                    .values
+                    .iter()
+                    .map(move |&(value_id, ref value)| {
+                        let mut combination = combination.clone();
+                        combination.push(SubCategoryValue {
+                            variable,
+                            value_id,
+                            value,
+                        });
+                        combination
+                    })
+            })
+            .collect();
+    }
+    combinations
+        .into_iter()
+        .map(|values| SubCategory { values })
+        .collect()
+}
+
+/// Resolves the variable values of a run done in the given category and level
+/// into their variables and labels.
+pub fn resolve_values<'a>(
+    values: &HashMap<VariableId, ValueId>,
+    category_id: CategoryId,
+    level_id: Option<LevelId>,
+    variables: &'a [Variable],
+) -> ResolvedValues<'a> {
+    let mut resolved = ResolvedValues {
+        values: Vec::new(),
+        unresolved: Vec::new(),
+    };
+    for (&variable_id, &value_id) in values {
+        let variable = match variables.iter().find(|v| v.id == variable_id) {
+            Some(variable) => variable,
+            None => {
+                resolved.unresolved.push(UnresolvedValue::UnknownVariable {
+                    variable_id,
+                    value_id,
+                });
+                continue;
+            }
+        };
+        let value = match variable.values.get(value_id) {
+            Some(value) => value,
+            None => {
+                resolved
+                    .unresolved
+                    .push(UnresolvedValue::UnknownValue { variable, value_id });
+                continue;
+            }
+        };
+        if variable.applies_to(category_id, level_id) {
+            resolved.values.push(SubCategoryValue {
+                variable,
+                value_id,
+                value,
+            });
+        } else {
+            resolved.unresolved.push(UnresolvedValue::OutOfScope {
+                variable,
+                value_id,
+                value,
+            });
+        }
+    }
+    resolved
+        .values
+        .sort_by(|a, b| (!a.is_subcategory(), a.name()).cmp(&(!b.is_subcategory(), b.name())));
+    resolved
+}
+
+pub async fn by_id(client: &Client, variable_id: VariableId) -> Result<Variable, Error> {
+    let mut url = api_url!(client, variables);
+    url.path_segments_mut().unwrap().push(variable_id.as_str());
+
+    execute_request(client, url).await
+}
+
+async fn variables_of(client: &Client, mut url: Url, id: &str) -> Result<Vec<Variable>, Error> {
+    url.path_segments_mut().unwrap().extend(&[id, "variables"]);
+
+    execute_request(client, url).await
+}
+
+pub async fn for_game(client: &Client, game_id: GameId) -> Result<Vec<Variable>, Error> {
+    variables_of(client, api_url!(client, games), game_id.as_str()).await
+}
+
+pub async fn for_category(
+    client: &Client,
+    category_id: CategoryId,
+) -> Result<Vec<Variable>, Error> {
+    variables_of(client, api_url!(client, categories), category_id.as_str()).await
+}
+
+pub async fn for_level(client: &Client, level_id: LevelId) -> Result<Vec<Variable>, Error> {
+    variables_of(client, api_url!(client, levels), level_id.as_str()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sub_categories, Variable};
+    use crate::categories::Category;
+
+    fn category(id: &str) -> Category {
+        serde_json::from_str(&format!(
+            r#"{{"id":"{}","weblink":"","name":"Any%","type":"per-game","rules":null}}"#,
+            id,
+        ))
+        .unwrap()
+    }
+
+    fn variable(id: &str, category: &str, scope: &str, values: &str, default: &str) -> Variable {
+        serde_json::from_str(&format!(
+            r#"{{
+                "id":"{}","name":"{}","category":{},"scope":{},
+                "values":{{"values":{},"default":{}}},
+                "mandatory":true,"is-subcategory":true
+            }}"#,
+            id, id, category, scope, values, default,
+        ))
+        .unwrap()
+    }
+
+    fn variables() -> Vec<Variable> {
+        vec![
+            variable(
+                "platform",
+                "null",
+                r#"{"type":"global"}"#,
+                r#"{"zconsole":{"label":"Console","rules":null},"aemu":{"label":"Emulator","rules":null}}"#,
+                r#""aemu""#,
+            ),
+            variable(
+                "version",
+                r#""anyp""#,
+                r#"{"type":"full-game"}"#,
+                r#"{"zjp":{"label":"JP","rules":null},"aus":{"label":"US","rules":null}}"#,
+                "null",
+            ),
+            variable(
+                "stage",
+                "null",
+                r#"{"type":"single-level","level":"bob"}"#,
+                r#"{"fast":{"label":"Fast","rules":null}}"#,
+                "null",
+            ),
+        ]
+    }
+
+    #[test]
+    fn values_keep_their_order() {
+        let variables = variables();
+        let labels = variables[0]
+            .values
+            .values
+            .iter()
+            .map(|(_, value)| &*value.label)
+            .collect::<Vec<_>>();
+        assert_eq!(labels, ["Console", "Emulator"]);
+        assert_eq!(variables[0].default_value().unwrap().0.as_str(), "aemu");
+        assert_eq!(variables[1].default_value().unwrap().0.as_str(), "zjp");
+    }
+
+    #[test]
+    fn variable_scopes() {
+        let variables = variables();
+        let any = "anyp".parse().unwrap();
+        let other = "other".parse().unwrap();
+        let bob = Some("bob".parse().unwrap());
+        let wf = Some("wf".parse().unwrap());
+
+        assert!(variables[0].applies_to(any, None));
+        assert!(variables[0].applies_to(other, bob));
+
+        assert!(variables[1].applies_to(any, None));
+        assert!(!variables[1].applies_to(other, None));
+        assert!(!variables[1].applies_to(any, bob));
+
+        assert!(variables[2].applies_to(any, bob));
+        assert!(!variables[2].applies_to(any, wf));
+        assert!(!variables[2].applies_to(any, None));
+    }
+
+    #[test]
+    fn sub_categories_follow_the_tabs() {
+        let variables = variables();
+        let tabs = sub_categories(&category("anyp"), None, &variables);
+        let names = tabs
+            .iter()
+            .map(|sub_category| sub_category.name())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["Console, JP", "Console, US", "Emulator, JP", "Emulator, US"],
+        );
+        let defaults = tabs
+            .iter()
+            .filter(|sub_category| sub_category.is_default())
+            .map(|sub_category| sub_category.name())
+            .collect::<Vec<_>>();
+        assert_eq!(defaults, ["Emulator, JP"]);
+
+        let tabs = sub_categories(&category("other"), None, &variables);
+        assert_eq!(tabs.len(), 2);
+
+        let tabs = sub_categories(&category("other"), Some("wf".parse().unwrap()), &[]);
+        assert_eq!(tabs.len(), 1);
+        assert!(tabs[0].values.is_empty());
+    }
+}
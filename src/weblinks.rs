@@ -0,0 +1,492 @@
+use crate::{
+    categories::Category,
+    common::{CategoryId, LevelId, ParseIdError, RunId, ValueId, VariableId},
+    games::{self, Game},
+    leaderboards::{self, Leaderboard, LeaderboardQuery},
+    levels::{self, Level},
+    runs::{self, Run},
+    series::{self, Series},
+    users::{self, User},
+    Client, Error,
+};
+use snafu::ResultExt;
+use std::str::FromStr;
+use url::Url;
+
+/// A reference to a resource on speedrun.com, parsed from the URL of its page
+/// on the website.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebLink {
+    /// The page of a game, possibly with one of its leaderboards selected.
+    Game {
+        abbreviation: Box<str>,
+        selection: Option<Selection>,
+    },
+    /// The page of an individual level of a game, possibly with one of its
+    /// leaderboards selected.
+    Level {
+        game_abbreviation: Box<str>,
+        level: Box<str>,
+        selection: Option<Selection>,
+    },
+    Run {
+        id: RunId,
+    },
+    User {
+        name: Box<str>,
+    },
+    Series {
+        abbreviation: Box<str>,
+    },
+}
+
+/// A leaderboard selected on the page of a game or level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Selection {
+    /// The category is selected by the anchor of the URL, which is the name of
+    /// the category with spaces replaced by underscores.
+    Anchor(Box<str>),
+    /// The leaderboard is selected by the `x` query parameter, which consists
+    /// of the ids of the level, category and sub-category values.
+    Ids {
+        level: Option<LevelId>,
+        category: CategoryId,
+        variables: Vec<(VariableId, ValueId)>,
+    },
+}
+
+/// The resource that a web link got resolved to.
+#[derive(Debug)]
+pub enum Resource {
+    Game(Box<Game>),
+    Leaderboard(Box<Leaderboard>),
+    Level(Level),
+    Run(Box<Run>),
+    User(Box<User>),
+    Series(Box<Series>),
+}
+
+#[derive(Debug, snafu::Snafu)]
+pub enum ParseError {
+    /// The web link is not a valid URL.
+    InvalidUrl { source: url::ParseError },
+    /// The web link doesn't point to speedrun.com.
+    ForeignHost,
+    /// The web link doesn't point to a page referencing a resource.
+    UnknownPage,
+    /// The web link contains an invalid id.
+    InvalidId { source: ParseIdError },
+}
+
+/// The pages of a game that don't refer to a specific level.
+const GAME_PAGES: &[&str] = &[
+    "full_game",
+    "forum",
+    "guides",
+    "guide",
+    "resources",
+    "resource",
+    "streams",
+    "news",
+    "gamestats",
+    "leaderboards",
+];
+
+/// The pages of a game that list its individual levels. Their anchor is the
+/// name of a level rather than a category.
+const LEVEL_PAGES: &[&str] = &["individual_levels", "levels"];
+
+/// The pages of a game that don't refer to any resource, so they can't be
+/// mistaken for levels.
+const NON_RESOURCE_PAGES: &[&str] = &[
+    "run",
+    "runs",
+    "user",
+    "editgame",
+    "editcategories",
+    "editlevels",
+    "editvariables",
+    "submit",
+    "submitrun",
+    "stats",
+    "modhub",
+    "moderators",
+    "followers",
+    "history",
+    "thread",
+];
+
+impl FromStr for WebLink {
+    type Err = ParseError;
+
+    fn from_str(web_link: &str) -> Result<Self, ParseError> {
+        let url = Url::parse(web_link).context(InvalidUrl)?;
+        match url.host_str() {
+            Some("speedrun.com") | Some("www.speedrun.com") => {}
+            _ => return Err(ParseError::ForeignHost),
+        }
+
+        let segments = url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        Ok(match segments[..] {
+            ["run", id] | [_, "run", id] | [_, "runs", id] => WebLink::Run {
+                id: id.parse().context(InvalidId)?,
+            },
+            ["user", name] | ["users", name] => WebLink::User { name: name.into() },
+            ["series", abbreviation] => WebLink::Series {
+                abbreviation: abbreviation.into(),
+            },
+            [game] => WebLink::Game {
+                abbreviation: game.into(),
+                selection: parse_selection(&url)?,
+            },
+            [game, page] if GAME_PAGES.contains(&page) => WebLink::Game {
+                abbreviation: game.into(),
+                selection: parse_selection(&url)?,
+            },
+            [game, page] if LEVEL_PAGES.contains(&page) => match parse_selection(&url)? {
+                Some(Selection::Anchor(level)) => WebLink::Level {
+                    game_abbreviation: game.into(),
+                    level,
+                    selection: None,
+                },
+                selection => WebLink::Game {
+                    abbreviation: game.into(),
+                    selection,
+                },
+            },
+            [_, page] if NON_RESOURCE_PAGES.contains(&page) => return Err(ParseError::UnknownPage),
+            [game, level] => WebLink::Level {
+                game_abbreviation: game.into(),
+                level: level.into(),
+                selection: parse_selection(&url)?,
+            },
+            _ => return Err(ParseError::UnknownPage),
+        })
+    }
+}
+
+fn parse_selection(url: &Url) -> Result<Option<Selection>, ParseError> {
+    if let Some((_, x)) = url.query_pairs().find(|(key, _)| key == "x") {
+        let mut parts = x.split('-');
+        let mut level = None;
+        let mut category = parts.next().unwrap_or_default();
+        if let Some(level_id) = category.strip_prefix("l_") {
+            level = Some(level_id.parse().context(InvalidId)?);
+            category = parts.next().unwrap_or_default();
+        }
+        let category = category.parse().context(InvalidId)?;
+        let variables = parts
+            .map(|part| {
+                let mut ids = part.splitn(2, '.');
+                let variable = ids.next().unwrap_or_default().parse()?;
+                let value = ids.next().unwrap_or_default().parse()?;
+                Ok((variable, value))
+            })
+            .collect::<Result<_, ParseIdError>>()
+            .context(InvalidId)?;
+        return Ok(Some(Selection::Ids {
+            level,
+            category,
+            variables,
+        }));
+    }
+    Ok(url
+        .fragment()
+        .filter(|anchor| !anchor.is_empty())
+        .map(|anchor| Selection::Anchor(anchor.into())))
+}
+
+fn category_by_anchor<'c>(categories: &'c [Category], anchor: &str) -> Result<&'c Category, Error> {
+    categories
+        .iter()
+        .find(|category| category.weblink.rsplit('#').next() == Some(anchor))
+        .ok_or(Error::UnresolvedWebLink)
+}
+
+fn query_for(variables: &[(VariableId, ValueId)]) -> LeaderboardQuery<'static> {
+    let mut query = LeaderboardQuery::new();
+    for &(variable, value) in variables {
+        query = query.variable(variable, value);
+    }
+    query
+}
+
+fn path_ends_with(web_link: &str, page: &str) -> bool {
+    web_link
+        .rsplit('/')
+        .next()
+        .is_some_and(|last| last.eq_ignore_ascii_case(page))
+}
+
+impl WebLink {
+    /// Retrieves the resource the web link refers to. If a leaderboard is
+    /// selected on the page of a game, the leaderboard is retrieved.
+    pub async fn resolve(&self, client: &Client) -> Result<Resource, Error> {
+        Ok(match self {
+            WebLink::Game {
+                abbreviation,
+                selection: None,
+            } => Resource::Game(Box::new(
+                games::by_abbreviation(client, abbreviation, games::Embeds::empty()).await?,
+            )),
+            WebLink::Game {
+                abbreviation,
+                selection: Some(Selection::Anchor(anchor)),
+            } => {
+                let game =
+                    games::by_abbreviation(client, abbreviation, games::Embeds::CATEGORIES).await?;
+                let categories = game.categories.as_ref().ok_or(Error::UnresolvedWebLink)?;
+                let category = category_by_anchor(&categories.data, anchor)?;
+                Resource::Leaderboard(Box::new(
+                    leaderboards::get(client, game.id, category.id, &LeaderboardQuery::new())
+                        .await?,
+                ))
+            }
+            WebLink::Game {
+                abbreviation,
+                selection:
+                    Some(Selection::Ids {
+                        level,
+                        category,
+                        variables,
+                    }),
+            } => {
+                let game =
+                    games::by_abbreviation(client, abbreviation, games::Embeds::empty()).await?;
+                let query = query_for(variables);
+                Resource::Leaderboard(Box::new(match level {
+                    Some(level) => {
+                        leaderboards::get_level(client, game.id, *level, *category, &query).await?
+                    }
+                    None => leaderboards::get(client, game.id, *category, &query).await?,
+                }))
+            }
+            WebLink::Level {
+                game_abbreviation,
+                level,
+                selection,
+            } => {
+                let game =
+                    games::by_abbreviation(client, game_abbreviation, games::Embeds::empty())
+                        .await?;
+                let level = levels::for_game(client, game.id)
+                    .await?
+                    .into_iter()
+                    .find(|l| path_ends_with(&l.weblink, level))
+                    .ok_or(Error::UnresolvedWebLink)?;
+                match selection {
+                    None => Resource::Level(level),
+                    Some(Selection::Anchor(anchor)) => {
+                        let categories = levels::categories(client, level.id).await?;
+                        let category = category_by_anchor(&categories, anchor)?;
+                        Resource::Leaderboard(Box::new(
+                            leaderboards::get_level(
+                                client,
+                                game.id,
+                                level.id,
+                                category.id,
+                                &LeaderboardQuery::new(),
+                            )
+                            .await?,
+                        ))
+                    }
+                    Some(Selection::Ids {
+                        level: level_id,
+                        category,
+                        variables,
+                    }) => Resource::Leaderboard(Box::new(
+                        leaderboards::get_level(
+                            client,
+                            game.id,
+                            level_id.unwrap_or(level.id),
+                            *category,
+                            &query_for(variables),
+                        )
+                        .await?,
+                    )),
+                }
+            }
+            WebLink::Run { id } => Resource::Run(Box::new(
+                runs::by_id(client, *id, runs::Embeds::empty()).await?,
+            )),
+            WebLink::User { name } => Resource::User(Box::new(users::by_name(client, name).await?)),
+            WebLink::Series { abbreviation } => Resource::Series(Box::new(
+                series::by_abbreviation(client, abbreviation).await?,
+            )),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParseError, Selection, WebLink};
+
+    fn parse(web_link: &str) -> WebLink {
+        web_link.parse().unwrap()
+    }
+
+    fn game(abbreviation: &str, selection: Option<Selection>) -> WebLink {
+        WebLink::Game {
+            abbreviation: abbreviation.into(),
+            selection,
+        }
+    }
+
+    #[test]
+    fn games() {
+        assert_eq!(parse("https://www.speedrun.com/sm64"), game("sm64", None));
+        assert_eq!(
+            parse("https://speedrun.com/sm64/full_game"),
+            game("sm64", None),
+        );
+        assert_eq!(
+            parse("https://www.speedrun.com/sm64/individual_levels"),
+            game("sm64", None),
+        );
+    }
+
+    #[test]
+    fn selection_by_ids() {
+        assert_eq!(
+            parse("https://www.speedrun.com/sm64?x=wkpoo02r-e8m7em86.9qj7z0oq"),
+            game(
+                "sm64",
+                Some(Selection::Ids {
+                    level: None,
+                    category: "wkpoo02r".parse().unwrap(),
+                    variables: vec![("e8m7em86".parse().unwrap(), "9qj7z0oq".parse().unwrap())],
+                }),
+            ),
+        );
+        assert_eq!(
+            parse("https://www.speedrun.com/sm64/individual_levels?x=l_rw6p6xd8-z27gg52d"),
+            game(
+                "sm64",
+                Some(Selection::Ids {
+                    level: Some("rw6p6xd8".parse().unwrap()),
+                    category: "z27gg52d".parse().unwrap(),
+                    variables: Vec::new(),
+                }),
+            ),
+        );
+        assert!(matches!(
+            "https://www.speedrun.com/sm64?x=l_rw6p6xd8".parse::<WebLink>(),
+            Err(ParseError::InvalidId { .. }),
+        ));
+    }
+
+    #[test]
+    fn anchors() {
+        assert_eq!(
+            parse("https://www.speedrun.com/sm64#120_Star"),
+            game("sm64", Some(Selection::Anchor("120_Star".into()))),
+        );
+        assert_eq!(
+            parse("https://www.speedrun.com/sm64/full_game#16_Star"),
+            game("sm64", Some(Selection::Anchor("16_Star".into()))),
+        );
+        assert_eq!(
+            parse("https://www.speedrun.com/sm64/individual_levels#Bob-omb_Battlefield"),
+            WebLink::Level {
+                game_abbreviation: "sm64".into(),
+                level: "Bob-omb_Battlefield".into(),
+                selection: None,
+            },
+        );
+    }
+
+    #[test]
+    fn levels() {
+        let level = |selection| WebLink::Level {
+            game_abbreviation: "sm64".into(),
+            level: "Bob-omb_Battlefield".into(),
+            selection,
+        };
+        assert_eq!(
+            parse("https://www.speedrun.com/sm64/Bob-omb_Battlefield"),
+            level(None),
+        );
+        assert_eq!(
+            parse("https://www.speedrun.com/sm64/Bob-omb_Battlefield#Stage_RTA"),
+            level(Some(Selection::Anchor("Stage_RTA".into()))),
+        );
+        assert_eq!(
+            parse("https://www.speedrun.com/sm64/Bob-omb_Battlefield?x=l_rw6p6xd8-z27gg52d"),
+            level(Some(Selection::Ids {
+                level: Some("rw6p6xd8".parse().unwrap()),
+                category: "z27gg52d".parse().unwrap(),
+                variables: Vec::new(),
+            })),
+        );
+    }
+
+    #[test]
+    fn runs() {
+        let id = "y8dwozoj".parse().unwrap();
+        assert_eq!(
+            parse("https://www.speedrun.com/run/y8dwozoj"),
+            WebLink::Run { id },
+        );
+        assert_eq!(
+            parse("https://www.speedrun.com/sm64/run/y8dwozoj"),
+            WebLink::Run { id },
+        );
+        assert_eq!(
+            parse("https://www.speedrun.com/sm64/runs/y8dwozoj"),
+            WebLink::Run { id },
+        );
+    }
+
+    #[test]
+    fn users() {
+        assert_eq!(
+            parse("https://www.speedrun.com/user/cheese"),
+            WebLink::User {
+                name: "cheese".into(),
+            },
+        );
+        assert_eq!(
+            parse("https://www.speedrun.com/users/cheese"),
+            WebLink::User {
+                name: "cheese".into(),
+            },
+        );
+    }
+
+    #[test]
+    fn series() {
+        assert_eq!(
+            parse("https://www.speedrun.com/series/mario"),
+            WebLink::Series {
+                abbreviation: "mario".into(),
+            },
+        );
+    }
+
+    #[test]
+    fn unknown_pages() {
+        for &web_link in &[
+            "https://www.speedrun.com/",
+            "https://www.speedrun.com/sm64/run",
+            "https://www.speedrun.com/sm64/runs",
+            "https://www.speedrun.com/sm64/user",
+            "https://www.speedrun.com/sm64/editgame",
+            "https://www.speedrun.com/sm64/submit",
+            "https://www.speedrun.com/sm64/Bob-omb_Battlefield/extra",
+        ] {
+            assert!(
+                matches!(web_link.parse::<WebLink>(), Err(ParseError::UnknownPage)),
+                "{}",
+                web_link,
+            );
+        }
+        assert!(matches!(
+            "https://example.com/sm64".parse::<WebLink>(),
+            Err(ParseError::ForeignHost),
+        ));
+    }
+}